@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use crate::game::{evaluate_guess, Guess};
+
+/// Number of words containing each letter from A to Z, counting each word once per letter. The
+/// words are only iterated once, so they can be streamed with
+/// [`words_iter`](crate::words::words_iter).
+pub fn letter_frequencies<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> [usize; 26] {
+    let mut frequencies = [0; 26];
+    for word in words {
+        let word = word.as_ref();
+        for (i, frequency) in frequencies.iter_mut().enumerate() {
+            if word.contains((b'a' + i as u8) as char) {
+                *frequency += 1;
+            }
+        }
+    }
+    frequencies
+}
+
+/// The `count` letters contained in the most words, most common first. Ties are broken
+/// alphabetically.
+pub fn top_letters<S: AsRef<str>>(words: impl IntoIterator<Item = S>, count: usize) -> Vec<char> {
+    let frequencies = letter_frequencies(words);
+    let mut letters: Vec<char> = ('a'..='z').collect();
+    letters.sort_by_key(|c| std::cmp::Reverse(frequencies[(*c as u8 - b'a') as usize]));
+    letters.truncate(count);
+    letters
+}
+
+/// Number of distinct letters of a word that are among the given letters.
+pub fn coverage(word: &str, letters: &[char]) -> usize {
+    letters.iter().filter(|c| word.contains(**c)).count()
+}
+
+/// Number of words with each letter from A to Z at each position, for words of `length` letters.
+pub fn positional_frequencies<S: AsRef<str>>(
+    words: impl IntoIterator<Item = S>,
+    length: usize,
+) -> Vec<[usize; 26]> {
+    let mut frequencies = vec![[0; 26]; length];
+    for word in words {
+        for (position, letter) in word.as_ref().bytes().enumerate().take(length) {
+            if letter.is_ascii_lowercase() {
+                frequencies[position][(letter - b'a') as usize] += 1;
+            }
+        }
+    }
+    frequencies
+}
+
+/// Difficulty of each position of a word, from 0 (easy) to 1 (hard). A position is hard when its
+/// letter is rare at that position compared to the most common letter there.
+pub fn position_difficulty(word: &str, frequencies: &[[usize; 26]]) -> Vec<f64> {
+    word.bytes()
+        .zip(frequencies)
+        .map(|(letter, counts)| {
+            let most_common = *counts.iter().max().unwrap_or(&0);
+            if !letter.is_ascii_lowercase() || most_common == 0 {
+                return 1.0;
+            }
+            1.0 - counts[(letter - b'a') as usize] as f64 / most_common as f64
+        })
+        .collect()
+}
+
+/// The word starting with `letter` that contains the most distinct of the `top` letters, as a
+/// suggested opener. Ties are broken by the most distinct letters overall, then alphabetically.
+/// Returns `None` if no word starts with `letter`.
+pub fn best_opener<'a>(words: &'a [String], letter: char, top: &[char]) -> Option<&'a str> {
+    let distinct = |word: &str| {
+        let mut letters = word.as_bytes().to_vec();
+        letters.sort_unstable();
+        letters.dedup();
+        letters.len()
+    };
+
+    words
+        .iter()
+        .filter(|w| w.starts_with(letter))
+        .max_by(|a, b| {
+            (coverage(a, top), distinct(a))
+                .cmp(&(coverage(b, top), distinct(b)))
+                .then_with(|| b.cmp(a))
+        })
+        .map(String::as_str)
+}
+
+/// Words that could still be the answer after the scored guesses of `board`: those that would have
+/// been given the same feedback for every guess.
+pub fn candidates<'a>(words: &'a [String], board: &[Guess]) -> Vec<&'a str> {
+    words
+        .iter()
+        .filter(|w| {
+            board
+                .iter()
+                .all(|g| evaluate_guess(&g.word, w) == g.correctness)
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// The guess that leaves the fewest candidates in the worst case, by splitting the candidates
+/// into groups that would get the same feedback and minimizing the largest group. Ties are broken
+/// in favor of guesses that could be the answer, then alphabetically.
+pub fn best_guess<'a>(guesses: &'a [String], candidates: &[&str]) -> Option<&'a str> {
+    let worst_case = |guess: &str| {
+        let mut groups: HashMap<Vec<u8>, usize> = HashMap::new();
+        for candidate in candidates {
+            let feedback = evaluate_guess(guess, candidate)
+                .iter()
+                .map(|c| c.glyph() as u8)
+                .collect();
+            *groups.entry(feedback).or_insert(0) += 1;
+        }
+        groups.into_values().max().unwrap_or(0)
+    };
+
+    guesses
+        .iter()
+        .map(|g| (worst_case(g), !candidates.contains(&g.as_str()), g))
+        .min()
+        .map(|(_, _, g)| g.as_str())
+}
+
+/// Estimated difficulty of a word, from 0 (easy) to 1 (hard): the mean difficulty of its
+/// positions.
+pub fn word_difficulty(word: &str, frequencies: &[[usize; 26]]) -> f64 {
+    let difficulties = position_difficulty(word, frequencies);
+    difficulties.iter().sum::<f64>() / difficulties.len().max(1) as f64
+}
+
+/// The hardest (or easiest) word by [`word_difficulty`] against `frequencies`. Ties are broken
+/// alphabetically, so the pick is deterministic.
+pub fn by_difficulty<'a>(
+    words: &'a [String],
+    frequencies: &[[usize; 26]],
+    hardest: bool,
+) -> Option<&'a str> {
+    words
+        .iter()
+        .map(|w| (word_difficulty(w, frequencies), w))
+        .min_by(|(a, x), (b, y)| {
+            let by_difficulty = if hardest {
+                b.total_cmp(a)
+            } else {
+                a.total_cmp(b)
+            };
+            by_difficulty.then_with(|| x.cmp(y))
+        })
+        .map(|(_, w)| w.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::parse_board;
+
+    #[test]
+    fn candidates_match_the_board() {
+        let words: Vec<String> = ["blimp", "crane", "plate", "slant", "slate"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+
+        assert_eq!(candidates(&words, &[]).len(), words.len());
+
+        let board = parse_board("crane:xxgxg", 5).unwrap();
+        assert_eq!(candidates(&words, &board), ["plate", "slate"]);
+
+        let board = parse_board("crane:xxgxg,plate:xgggg", 5).unwrap();
+        assert_eq!(candidates(&words, &board), ["slate"]);
+    }
+}
@@ -0,0 +1,683 @@
+use std::io::{BufRead, IsTerminal};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use crate::game::HintType;
+use crate::words::{sanitize_word, Validity};
+
+/// Lines arriving within this long of a line that was read are treated as part of the same paste.
+const PASTE_WINDOW: Duration = Duration::from_millis(25);
+
+/// Wordle configuration.
+pub struct Configuration {
+    /// Number of guess tries before the game is over.
+    pub guess_tries: u32,
+
+    /// Number of letters in the word to be guessed.
+    pub guess_letters: u8,
+
+    /// File of additional words accepted as guesses, on top of the possible answers.
+    pub allowed_file: Option<String>,
+
+    /// Print a note when a guess is allowed but cannot be the answer.
+    pub warn_non_answer: bool,
+
+    /// Disable colored output, encoding correctness in the letters themselves instead.
+    pub no_color: bool,
+
+    /// Letters that the target word must all contain. Narrows the pool of possible answers, so
+    /// any selection from the pool (seeded or not) picks from the narrowed pool.
+    pub require_letters: Option<String>,
+
+    /// Refuse guesses that use a letter from any previous guess.
+    pub no_letter_reuse: bool,
+
+    /// Show a menu to play again, show stats, or quit after each game. Only shown when standard
+    /// input is a terminal.
+    pub menu: bool,
+
+    /// List the anagrams of the target word at the end of the game.
+    pub show_anagrams: bool,
+
+    /// Show a line of the letters that have not been tried yet.
+    pub letters_left: bool,
+
+    /// Number of hints available at the start of the game. A hint is requested by entering
+    /// `hint` at the prompt, and does not use up a guess try.
+    pub hints: u32,
+
+    /// Grant a new hint every this many guesses (0 to disable). Regenerated hints accrue on top
+    /// of the initial `hints` budget, but only while fewer than
+    /// [`MAX_REGENERATED_HINTS`](crate::game::MAX_REGENERATED_HINTS) hints are unspent.
+    pub hint_regen: u32,
+
+    /// Seed used to pick the target word of the first game. Later games in the same run use the
+    /// following seeds. Picked at random when not set.
+    pub seed: Option<u64>,
+
+    /// Shuffle the order of the tried letters display, using the game's seed. This is cosmetic
+    /// only, and does not change the game.
+    pub scramble_keyboard: bool,
+
+    /// Play this word instead of picking one from the answers. It does not need to be in any
+    /// word list.
+    pub target: Option<String>,
+
+    /// Write an SVG image of the final board to this path.
+    pub svg: Option<String>,
+
+    /// Refuse a first guess that contains fewer than `opener_min` of the `opener_top` letters
+    /// most common among the answers.
+    pub require_opener_coverage: bool,
+
+    /// Number of most common letters considered by `require_opener_coverage` and by opener
+    /// suggestions.
+    pub opener_top: usize,
+
+    /// Number of the most common letters a first guess must contain under
+    /// `require_opener_coverage`.
+    pub opener_min: usize,
+
+    /// Compare words as they are, instead of lowercasing the word lists, target, and guesses.
+    pub case_sensitive: bool,
+
+    /// Print a shareable emoji grid of the board at the end of the game, without the letters.
+    pub share: bool,
+
+    /// Add a line of stats under the share grid. Implies `share`.
+    pub share_stats: bool,
+
+    /// Code printed when quitting a game with `quit`, to continue that game.
+    pub resume_code: Option<String>,
+
+    /// Only show how many letters of each guess are correct or present during the game, and
+    /// reveal the colored board once it is over.
+    pub blind: bool,
+
+    /// Which word list guesses are validated against. Defaults to the answers, unless an
+    /// `allowed_file` is given, in which case it defaults to the full allowed list.
+    pub validity: Validity,
+
+    /// Show how hard each position of the target word was at the end of the game.
+    pub heatmap: bool,
+
+    /// Enable helper commands at the prompt, such as `opener X` to suggest an opener starting
+    /// with X. Helper commands do not use up a guess try.
+    pub helpers: bool,
+
+    /// Suggest an opener starting with this letter at the start of the game. Requires `helpers`.
+    pub opener_letter: Option<char>,
+
+    /// Host a race on this address, for players joining with `join`.
+    pub host: Option<String>,
+
+    /// Join the race hosted on this address.
+    pub join: Option<String>,
+
+    /// Show what the colors of a guess mean at the start of the game. On by default, since no
+    /// play history is kept to tell first-time players apart.
+    pub legend: bool,
+
+    /// After each guess, show the letters whose state it changed.
+    pub keyboard_diff: bool,
+
+    /// How the target word is picked from the pool.
+    pub selection: Selection,
+
+    /// After losing a game, keep guessing (without affecting the result) until the word is
+    /// found or `done` is entered.
+    pub study_on_loss: bool,
+
+    /// Print extra information at startup, such as the sizes of the loaded word lists.
+    pub verbose: bool,
+
+    /// Print each scored guess in large block letters, when the terminal is wide enough.
+    pub big_text: bool,
+
+    /// What the `hint` command reveals.
+    pub hint_type: HintType,
+
+    /// In a session of several games, pick each target word among those sharing at least this
+    /// many letters with the previous one.
+    pub ladder: Option<usize>,
+
+    /// Load and check the configuration and word lists, and pick a target word, then exit
+    /// without playing.
+    pub dry_run: bool,
+
+    /// Show the letters known to be in their correct position in the input prompt.
+    pub show_template: bool,
+
+    /// Leave words with repeated letters out of the pool of target words.
+    pub no_duplicates: bool,
+
+    /// Pick words with repeated letters as the target less often, instead of never.
+    pub prefer_unique: bool,
+
+    /// Print a JSON summary of each finished game.
+    pub report: bool,
+
+    /// Only accept guesses made of these letters, and pick a target word made of them. Applies on
+    /// top of the other filters of the pool, such as `require_letters`.
+    pub letter_bank: Option<String>,
+
+    /// Color tiles with exact RGB colors, unless the terminal reports that it doesn't support
+    /// them.
+    pub truecolor: bool,
+
+    /// Read guesses from this file instead of standard input.
+    pub guesses_file: Option<String>,
+
+    /// Describe each row of the share grid in words after it.
+    pub alt_text: bool,
+
+    /// Report at the end of the game whether at least this many different letters were tried.
+    /// Nothing is refused for missing the goal.
+    pub coverage_goal: Option<usize>,
+
+    /// Suggest guesses for a board played elsewhere, instead of playing a game.
+    pub solve_from: bool,
+
+    /// Board to suggest guesses for, as comma-separated `guess:feedback` entries.
+    pub board: Option<String>,
+
+    /// Show the number of possible answers left in the input prompt.
+    pub count_in_prompt: bool,
+
+    /// Keep apostrophes and hyphens in the words of word lists, as part of the word. By default,
+    /// words containing them are dropped.
+    pub allow_punctuation: bool,
+
+    /// Analyze the word lists instead of playing a game.
+    pub analyze: bool,
+
+    /// Print how often each letter is at each position of the answers, or of the words in
+    /// `allowed_file` if given, as CSV.
+    pub matrix_csv: bool,
+
+    /// After a won game, show which guess first found each letter in its correct position.
+    pub review: bool,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Configuration {
+            guess_tries: 6,
+            guess_letters: 5,
+            allowed_file: None,
+            warn_non_answer: false,
+            no_color: false,
+            require_letters: None,
+            no_letter_reuse: false,
+            menu: false,
+            show_anagrams: false,
+            letters_left: false,
+            hints: 0,
+            hint_regen: 0,
+            seed: None,
+            scramble_keyboard: false,
+            target: None,
+            svg: None,
+            require_opener_coverage: false,
+            opener_top: 10,
+            opener_min: 3,
+            case_sensitive: false,
+            share: false,
+            share_stats: false,
+            resume_code: None,
+            blind: false,
+            validity: Validity::Answers,
+            heatmap: false,
+            helpers: false,
+            opener_letter: None,
+            host: None,
+            join: None,
+            legend: true,
+            keyboard_diff: false,
+            selection: Selection::Random,
+            study_on_loss: false,
+            verbose: false,
+            big_text: false,
+            hint_type: HintType::Green,
+            ladder: None,
+            dry_run: false,
+            show_template: false,
+            no_duplicates: false,
+            prefer_unique: false,
+            report: false,
+            letter_bank: None,
+            truecolor: false,
+            guesses_file: None,
+            alt_text: false,
+            coverage_goal: None,
+            solve_from: false,
+            board: None,
+            count_in_prompt: false,
+            allow_punctuation: false,
+            analyze: false,
+            matrix_csv: false,
+            review: false,
+        }
+    }
+}
+
+impl Configuration {
+    /// Build a configuration from command line arguments (excluding the program name).
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Configuration, String> {
+        let mut config = Configuration::default();
+        let mut args = args.into_iter();
+        let mut validity = None;
+        let mut inline = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--allowed" => config.allowed_file = Some(value(&arg, args.next())?),
+                "--warn-non-answer" => config.warn_non_answer = true,
+                "--no-color" => config.no_color = true,
+                "--no-letter-reuse" => config.no_letter_reuse = true,
+                "--menu" => config.menu = true,
+                "--show-anagrams" => config.show_anagrams = true,
+                "--letters-left" => config.letters_left = true,
+                "--hints" => config.hints = number(&arg, args.next())?,
+                "--hint-regen" => config.hint_regen = number(&arg, args.next())?,
+                "--seed" => config.seed = Some(number(&arg, args.next())?),
+                "--scramble-keyboard" => config.scramble_keyboard = true,
+                "--require-opener-coverage" => config.require_opener_coverage = true,
+                "--opener-top" => config.opener_top = number(&arg, args.next())?,
+                "--opener-min" => config.opener_min = number(&arg, args.next())?,
+                "--share" => config.share = true,
+                "--share-stats" => {
+                    config.share = true;
+                    config.share_stats = true;
+                }
+                "--alt-text" => {
+                    config.share = true;
+                    config.alt_text = true;
+                }
+                "--blind" => config.blind = true,
+                "--heatmap" => config.heatmap = true,
+                "--helpers" => config.helpers = true,
+                "--keyboard-diff" => config.keyboard_diff = true,
+                "--study-on-loss" => config.study_on_loss = true,
+                "--verbose" => config.verbose = true,
+                "--big-text" => config.big_text = true,
+                "--review" => config.review = true,
+                "--allow-punctuation" => config.allow_punctuation = true,
+                "--count-in-prompt" => config.count_in_prompt = true,
+                "analyze" => config.analyze = true,
+                "--matrix-csv" => config.matrix_csv = true,
+                "solve-from" => config.solve_from = true,
+                "--guesses" => config.board = Some(value(&arg, args.next())?),
+                "--config-inline" => inline = Some(value(&arg, args.next())?),
+                "--coverage-goal" => config.coverage_goal = Some(number(&arg, args.next())?),
+                "--guesses-file" => config.guesses_file = Some(value(&arg, args.next())?),
+                "--truecolor" => config.truecolor = true,
+                "--letter-bank" => config.letter_bank = Some(value(&arg, args.next())?),
+                "--report" => config.report = true,
+                "--no-duplicates" => config.no_duplicates = true,
+                "--prefer-unique" => config.prefer_unique = true,
+                "--show-template" => config.show_template = true,
+                "--dry-run" => config.dry_run = true,
+                "--ladder" => config.ladder = Some(number(&arg, args.next())?),
+                "--hint-type" => config.hint_type = value(&arg, args.next())?.parse()?,
+                "--hardest" => config.selection = Selection::Hardest,
+                "--easiest" => config.selection = Selection::Easiest,
+                "--legend" => config.legend = true,
+                "--no-legend" => config.legend = false,
+                "--host" => config.host = Some(value(&arg, args.next())?),
+                "--join" => config.join = Some(value(&arg, args.next())?),
+                "--opener-letter" => config.opener_letter = Some(letter(&arg, args.next())?),
+                "--validity" => validity = Some(value(&arg, args.next())?.parse()?),
+                "--resume-code" => config.resume_code = Some(value(&arg, args.next())?),
+                "--svg" => config.svg = Some(value(&arg, args.next())?),
+                "--target" => config.target = Some(value(&arg, args.next())?),
+                "--require-letters" => config.require_letters = Some(value(&arg, args.next())?),
+                "--case-sensitive" => config.case_sensitive = true,
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+
+        // Inline settings take precedence over the individual options
+        if let Some(inline) = inline {
+            config.apply_inline(&inline)?;
+        }
+
+        config.validity = validity.unwrap_or(match config.allowed_file {
+            Some(_) => Validity::Full,
+            None => Validity::Answers,
+        });
+
+        if config.selection != Selection::Random
+            && (config.seed.is_some() || config.target.is_some())
+        {
+            return Err(String::from(
+                "'--hardest' and '--easiest' cannot be used with '--seed' or '--target'",
+            ));
+        }
+
+        if config.solve_from != config.board.is_some() {
+            return Err(String::from(
+                "'solve-from' and '--guesses' must be used together",
+            ));
+        }
+
+        if config.analyze != config.matrix_csv {
+            return Err(String::from(
+                "'analyze' and '--matrix-csv' must be used together",
+            ));
+        }
+
+        if config.host.is_some() && config.join.is_some() {
+            return Err(String::from(
+                "'--host' and '--join' cannot be used together",
+            ));
+        }
+
+        // Racing players share a seed, which a custom target would ignore
+        if (config.host.is_some() || config.join.is_some()) && config.target.is_some() {
+            return Err(String::from("'--target' cannot be used in a race"));
+        }
+
+        if config.no_duplicates && config.prefer_unique {
+            return Err(String::from(
+                "'--no-duplicates' and '--prefer-unique' cannot be used together",
+            ));
+        }
+
+        // A first guess can't contain more of the top letters than there are (at most 26)
+        if config.opener_min > config.opener_top.min(26) {
+            return Err(String::from(
+                "'--opener-min' cannot be larger than '--opener-top'",
+            ));
+        }
+
+        if config.opener_letter.is_some() && !config.helpers {
+            return Err(String::from("'--opener-letter' requires '--helpers'"));
+        }
+
+        // Normalize words only once it is known whether case matters
+        let case_sensitive = config.case_sensitive;
+        let allow_punctuation = config.allow_punctuation;
+        config.target = config
+            .target
+            .map(|w| sanitize_word(&w, case_sensitive, allow_punctuation));
+        config.require_letters = config
+            .require_letters
+            .map(|w| sanitize_word(&w, case_sensitive, false));
+        config.letter_bank = config
+            .letter_bank
+            .map(|w| sanitize_word(&w, case_sensitive, false));
+
+        if let Some(target) = &config.target {
+            if target.len() != config.guess_letters as usize {
+                return Err(format!(
+                    "the target word must be {} letters in length",
+                    config.guess_letters
+                ));
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Apply comma-separated `key=value` settings, such as `tries=4,letters=6`. The keys are
+    /// `tries`, `letters`, `hints`, and `seed`.
+    fn apply_inline(&mut self, inline: &str) -> Result<(), String> {
+        for setting in inline.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let Some((key, value)) = setting.split_once('=') else {
+                return Err(format!("inline setting '{}' expects key=value", setting));
+            };
+            let (key, value) = (key.trim(), Some(value.trim().to_string()));
+
+            match key {
+                "tries" => self.guess_tries = number(key, value)?,
+                "letters" => self.guess_letters = number(key, value)?,
+                "hints" => self.hints = number(key, value)?,
+                "seed" => self.seed = Some(number(key, value)?),
+                _ => {
+                    return Err(format!(
+                        "unknown inline setting '{}' (expected tries, letters, hints, or seed)",
+                        key
+                    ))
+                }
+            }
+        }
+
+        if self.guess_tries == 0 || self.guess_letters == 0 {
+            return Err(String::from("'tries' and 'letters' must be at least 1"));
+        }
+
+        Ok(())
+    }
+}
+
+/// How the target word is picked from the pool of possible answers.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Selection {
+    /// Pick a word at random, using the game's seed.
+    Random,
+
+    /// Pick the word with the highest estimated difficulty. This is deterministic (ties are
+    /// broken alphabetically), so every game picks the same word from the same pool.
+    Hardest,
+
+    /// Pick the word with the lowest estimated difficulty, deterministically like `Hardest`.
+    Easiest,
+}
+
+/// Reader of input lines, for the guess prompt and menus.
+///
+/// Lines are read on a background thread. When pasted lines are discarded, a line that is read
+/// is returned alone, and any other lines already waiting (such as the rest of an accidental
+/// multi-line paste) are dropped, so that they are not submitted as further guesses.
+pub struct Input {
+    /// Lines read so far, without their line endings.
+    lines: Receiver<String>,
+
+    /// Drop the lines arriving right after a line that was read.
+    discard_pasted: bool,
+
+    /// Print each line that is read, as it would appear if typed.
+    echo: bool,
+}
+
+impl Input {
+    /// Read lines from standard input. Pasted lines are discarded when standard input is a
+    /// terminal, while piped input is read line by line in full.
+    pub fn stdin() -> Input {
+        let discard_pasted = std::io::stdin().is_terminal();
+        Input::from_reader(std::io::BufReader::new(std::io::stdin()), discard_pasted)
+    }
+
+    /// Read lines from any reader.
+    pub fn from_reader(reader: impl BufRead + Send + 'static, discard_pasted: bool) -> Input {
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in reader.lines() {
+                if line.map_or(true, |line| sender.send(line).is_err()) {
+                    break;
+                }
+            }
+        });
+
+        Input {
+            lines,
+            discard_pasted,
+            echo: false,
+        }
+    }
+
+    /// Read lines from a file, printing each line as it is read. Every line is kept, as the
+    /// file is expected to hold one guess per line.
+    pub fn from_file(path: &str) -> Result<Input, String> {
+        let file =
+            std::fs::File::open(path).map_err(|e| format!("could not read '{}': {}", path, e))?;
+        let mut input = Input::from_reader(std::io::BufReader::new(file), false);
+        input.echo = true;
+        Ok(input)
+    }
+
+    /// Read the next line, or `None` at the end of input.
+    pub fn read_line(&mut self) -> Option<String> {
+        let line = self.lines.recv().ok()?;
+
+        if self.echo {
+            println!("{}", line);
+        }
+
+        if self.discard_pasted {
+            let mut discarded = 0;
+            while self.lines.recv_timeout(PASTE_WINDOW).is_ok() {
+                discarded += 1;
+            }
+            if discarded > 0 {
+                println!("Ignored {} more pasted line(s).", discarded);
+            }
+        }
+
+        Some(line)
+    }
+
+    /// Drop the lines already waiting to be read, such as guesses typed ahead after the game was
+    /// over, so that they are not taken as answers to the next prompt. Only interactive input is
+    /// dropped, as piped input is read in full. Returns the number of lines dropped.
+    pub fn discard_waiting(&mut self) -> usize {
+        if !self.discard_pasted {
+            return 0;
+        }
+
+        std::iter::from_fn(|| self.lines.try_recv().ok()).count()
+    }
+}
+
+/// Choice made in the end-of-game menu.
+pub enum MenuChoice {
+    /// Start a new game.
+    PlayAgain,
+
+    /// Show the stats of the games played so far.
+    Stats,
+
+    /// Exit the program.
+    Quit,
+}
+
+impl MenuChoice {
+    /// Parse a menu response, accepting the first letter or the full word.
+    pub fn parse(input: &str) -> Option<MenuChoice> {
+        match input.trim().to_lowercase().as_str() {
+            "p" | "play" => Some(MenuChoice::PlayAgain),
+            "s" | "stats" => Some(MenuChoice::Stats),
+            "q" | "quit" => Some(MenuChoice::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Unwrap the value following an option.
+fn value(option: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("'{}' expects a value", option))
+}
+
+/// Unwrap the single letter following an option.
+fn letter(option: &str, value: Option<String>) -> Result<char, String> {
+    let value = self::value(option, value)?;
+    match value.chars().collect::<Vec<_>>()[..] {
+        [letter] if letter.is_ascii_alphabetic() => Ok(letter.to_ascii_lowercase()),
+        _ => Err(format!("'{}' expects a letter, got '{}'", option, value)),
+    }
+}
+
+/// Unwrap and parse the numeric value following an option.
+fn number<T: std::str::FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = self::value(option, value)?;
+    value
+        .parse()
+        .map_err(|_| format!("'{}' expects a number, got '{}'", option, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pasted_lines_are_discarded() {
+        let mut input = Input::from_reader(std::io::Cursor::new("crane\nslate\nblimp\n"), true);
+        assert_eq!(input.read_line().as_deref(), Some("crane"));
+        assert_eq!(input.read_line(), None);
+    }
+
+    #[test]
+    fn piped_lines_are_kept() {
+        let mut input = Input::from_reader(std::io::Cursor::new("crane\nslate\n"), false);
+        assert_eq!(input.read_line().as_deref(), Some("crane"));
+        assert_eq!(input.read_line().as_deref(), Some("slate"));
+        assert_eq!(input.read_line(), None);
+    }
+
+    /// Input with the given lines already waiting to be read.
+    fn waiting(lines: &[&str], discard_pasted: bool) -> Input {
+        let (sender, receiver) = mpsc::channel();
+        for line in lines {
+            sender.send(line.to_string()).unwrap();
+        }
+        Input {
+            lines: receiver,
+            discard_pasted,
+            echo: false,
+        }
+    }
+
+    #[test]
+    fn waiting_lines_are_discarded_when_interactive() {
+        let mut input = waiting(&["y", "n"], true);
+        assert_eq!(input.discard_waiting(), 2);
+        assert_eq!(input.discard_waiting(), 0);
+
+        let mut input = waiting(&["y", "n"], false);
+        assert_eq!(input.discard_waiting(), 0);
+        assert_eq!(input.read_line().as_deref(), Some("y"));
+    }
+
+    #[test]
+    fn menu_choices_are_parsed() {
+        for play in ["p", "play", " P ", "PLAY"] {
+            assert!(matches!(
+                MenuChoice::parse(play),
+                Some(MenuChoice::PlayAgain)
+            ));
+        }
+        assert!(matches!(MenuChoice::parse("s"), Some(MenuChoice::Stats)));
+        assert!(matches!(MenuChoice::parse("quit"), Some(MenuChoice::Quit)));
+        for other in ["", "y", "n", "pl", "plays", "crane"] {
+            assert!(MenuChoice::parse(other).is_none(), "{}", other);
+        }
+    }
+
+    #[test]
+    fn inline_settings_are_applied() {
+        let mut config = Configuration::default();
+        config
+            .apply_inline("tries=8, letters=6,hints=2,seed=42")
+            .unwrap();
+        assert_eq!(config.guess_tries, 8);
+        assert_eq!(config.guess_letters, 6);
+        assert_eq!(config.hints, 2);
+        assert_eq!(config.seed, Some(42));
+    }
+
+    #[test]
+    fn invalid_inline_settings_are_refused() {
+        let error = |inline| Configuration::default().apply_inline(inline).unwrap_err();
+        assert_eq!(
+            error("color=red"),
+            "unknown inline setting 'color' (expected tries, letters, hints, or seed)"
+        );
+        assert_eq!(error("tries"), "inline setting 'tries' expects key=value");
+        assert_eq!(error("tries=0"), "'tries' and 'letters' must be at least 1");
+        assert_eq!(
+            error("letters=five"),
+            "'letters' expects a number, got 'five'"
+        );
+    }
+}
@@ -0,0 +1,890 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::analysis;
+use crate::cli::Configuration;
+
+/// Correctness of a letter used in a position.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Correctness {
+    /// The correct letter is in the correct position.
+    Correct,
+
+    /// A correct letter is in an incorrect position.
+    CorrectLetter,
+
+    /// An incorrect (and unused) letter is used in an incorrect position.
+    Incorrect,
+}
+
+/// Glyph that could not be parsed as a [`Correctness`].
+#[derive(Debug, PartialEq)]
+pub struct InvalidGlyph(pub char);
+
+impl std::fmt::Display for InvalidGlyph {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' is not a correctness glyph (expected g, y, x, or a tile emoji)",
+            self.0
+        )
+    }
+}
+
+impl Correctness {
+    /// Parse a correctness glyph: `g`, `y`, and `x` (in either case), or the 🟩, 🟨, ⬛, and ⬜
+    /// tile emoji.
+    pub fn from_glyph(glyph: char) -> Result<Correctness, InvalidGlyph> {
+        match glyph {
+            'g' | 'G' | '🟩' => Ok(Correctness::Correct),
+            'y' | 'Y' | '🟨' => Ok(Correctness::CorrectLetter),
+            'x' | 'X' | '⬛' | '⬜' => Ok(Correctness::Incorrect),
+            _ => Err(InvalidGlyph(glyph)),
+        }
+    }
+
+    /// ASCII glyph of the correctness: `g`, `y`, or `x`.
+    pub fn glyph(self) -> char {
+        match self {
+            Correctness::Correct => 'g',
+            Correctness::CorrectLetter => 'y',
+            Correctness::Incorrect => 'x',
+        }
+    }
+
+    /// Tile emoji of the correctness: 🟩, 🟨, or ⬛.
+    pub fn emoji(self) -> char {
+        match self {
+            Correctness::Correct => '🟩',
+            Correctness::CorrectLetter => '🟨',
+            Correctness::Incorrect => '⬛',
+        }
+    }
+}
+
+impl TryFrom<char> for Correctness {
+    type Error = InvalidGlyph;
+
+    fn try_from(glyph: char) -> Result<Self, Self::Error> {
+        Correctness::from_glyph(glyph)
+    }
+}
+
+/// Parse a string of correctness glyphs, one per letter of a guess.
+pub fn parse_feedback(feedback: &str) -> Result<Vec<Correctness>, InvalidGlyph> {
+    feedback.chars().map(Correctness::from_glyph).collect()
+}
+
+/// Parse a board of comma-separated `guess:feedback` entries, such as `slate:xxyxg,crony:xgxxx`,
+/// into scored guesses of `length` letters.
+pub fn parse_board(board: &str, length: usize) -> Result<Vec<Guess>, String> {
+    board
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (word, feedback) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("board entry '{}' expects guess:feedback", entry))?;
+            let word = word.trim().to_lowercase();
+            let correctness = parse_feedback(feedback.trim()).map_err(|e| e.to_string())?;
+
+            if word.len() != length || correctness.len() != length {
+                return Err(format!(
+                    "board entry '{}' must have a {}-letter guess and {} feedback glyphs",
+                    entry, length, length
+                ));
+            }
+
+            Ok(Guess { word, correctness })
+        })
+        .collect()
+}
+
+/// Result of the game that was played.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameResult {
+    /// The player won.
+    Success,
+
+    /// The player lost.
+    Failure,
+}
+
+/// Tally of the games played in one run of the program.
+#[derive(Default)]
+pub struct Session {
+    /// Number of games played.
+    pub played: u32,
+
+    /// Number of games won.
+    pub won: u32,
+}
+
+impl Session {
+    /// Record the result of a finished game.
+    pub fn record(&mut self, result: &GameResult) {
+        self.played += 1;
+        if let GameResult::Success = result {
+            self.won += 1;
+        }
+    }
+}
+
+/// Reason a guess was refused. Refused guesses do not use up a guess try.
+pub enum InvalidGuess {
+    /// The guess does not have the same number of letters as the target word.
+    WrongLength(u8),
+
+    /// The guess is not in the word list.
+    UnknownWord,
+
+    /// The guess uses a letter from a previous guess, while letter reuse is forbidden.
+    LetterReused(char),
+
+    /// The guess uses a letter that is not in the letter bank.
+    OutsideBank(char),
+
+    /// The first guess contains too few of the most common letters.
+    WeakOpener {
+        /// The most common letters.
+        letters: Vec<char>,
+
+        /// Number of the most common letters the guess must contain.
+        required: usize,
+    },
+}
+
+impl std::fmt::Display for InvalidGuess {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InvalidGuess::WrongLength(letters) => {
+                write!(f, "The word is {} letters in length.", letters)
+            }
+            InvalidGuess::UnknownWord => write!(f, "Please use a valid word."),
+            InvalidGuess::LetterReused(letter) => {
+                write!(f, "The letter '{}' has already been used.", letter)
+            }
+            InvalidGuess::OutsideBank(letter) => {
+                write!(f, "The letter '{}' is not in the letter bank.", letter)
+            }
+            InvalidGuess::WeakOpener { letters, required } => write!(
+                f,
+                "The first guess must contain at least {} of: {}",
+                required,
+                letters.iter().collect::<String>()
+            ),
+        }
+    }
+}
+
+/// A scored guess.
+pub struct Guess {
+    /// The guessed word.
+    pub word: String,
+
+    /// Correctness of each letter of the guessed word, in order.
+    pub correctness: Vec<Correctness>,
+}
+
+impl Guess {
+    /// Number of letters in their correct position, and number of letters in an incorrect
+    /// position.
+    pub fn counts(&self) -> (usize, usize) {
+        let count = |c| self.correctness.iter().filter(|x| **x == c).count();
+        (
+            count(Correctness::Correct),
+            count(Correctness::CorrectLetter),
+        )
+    }
+}
+
+/// Score a guess against the target word.
+pub fn evaluate_guess(guess: &str, target: &str) -> Vec<Correctness> {
+    let guess = guess.as_bytes();
+    let target = target.as_bytes();
+
+    let mut correctness = vec![Correctness::Incorrect; guess.len()];
+
+    // Number of times a certain letter has been marked as contained in the target word
+    let mut letter_count: HashMap<u8, usize> = HashMap::new();
+
+    // Mark correctly placed letters
+    for (i, (g, t)) in guess.iter().zip(target).enumerate() {
+        if g == t {
+            *letter_count.entry(*g).or_insert(0) += 1;
+            correctness[i] = Correctness::Correct;
+        }
+    }
+
+    // Mark existing letters, as long as the letter has not already been marked as many times as it
+    // is contained in the target word
+    for (i, letter) in guess.iter().enumerate() {
+        if correctness[i] == Correctness::Correct {
+            continue;
+        }
+
+        let count = letter_count.entry(*letter).or_insert(0);
+        if *count < target.iter().filter(|c| *c == letter).count() {
+            *count += 1;
+            correctness[i] = Correctness::CorrectLetter;
+        }
+    }
+
+    correctness
+}
+
+/// Regenerated hints stop accruing while this many hints are unspent.
+pub const MAX_REGENERATED_HINTS: u32 = 3;
+
+/// A letter of the target word revealed by a hint.
+pub struct Hint {
+    /// Position of the letter in the target word, starting at 0.
+    pub position: usize,
+
+    /// The revealed letter.
+    pub letter: char,
+}
+
+/// What a hint reveals.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HintType {
+    /// A letter of the target word, in its position.
+    Green,
+
+    /// A letter that is not in the target word. This gives away less than a green hint.
+    Absent,
+}
+
+impl std::str::FromStr for HintType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "green" => Ok(HintType::Green),
+            "absent" => Ok(HintType::Absent),
+            _ => Err(format!(
+                "unknown hint type '{}' (expected green or absent)",
+                s
+            )),
+        }
+    }
+}
+
+/// Hints left, and what hints revealed so far, to carry over to a resumed game.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct HintState {
+    /// Number of unspent hints.
+    pub hints: u32,
+
+    /// Positions of the target word revealed by hints.
+    pub revealed: Vec<usize>,
+
+    /// Letters revealed by hints not to be in the target word.
+    pub revealed_absent: Vec<char>,
+}
+
+/// A command typed at the guess prompt instead of a guess.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Command {
+    /// Stop playing, to resume the game later.
+    Quit,
+
+    /// Reveal something about the word without using up a guess try.
+    Hint,
+
+    /// Suggest an opener starting with a letter, or `None` if no single letter was given.
+    Opener(Option<char>),
+}
+
+impl Command {
+    /// Parse a command from trimmed input, or `None` if the input is not a command and should be
+    /// guessed. The `opener` command is only available with helpers.
+    pub fn parse(input: &str, helpers: bool) -> Option<Command> {
+        match input {
+            "quit" => Some(Command::Quit),
+            "hint" => Some(Command::Hint),
+            _ => {
+                let letter = input.strip_prefix("opener ").filter(|_| helpers)?;
+                match letter.trim().chars().collect::<Vec<_>>()[..] {
+                    [letter] => Some(Command::Opener(Some(letter))),
+                    _ => Some(Command::Opener(None)),
+                }
+            }
+        }
+    }
+}
+
+/// State of a single game.
+pub struct Game {
+    /// The word the player is trying to guess.
+    target: String,
+
+    /// Number of guess tries before the game is over.
+    tries: u32,
+
+    /// Guesses made by the player, in order.
+    history: Vec<Guess>,
+
+    /// Letters tried by the player, and the number of instances of the letter in the target word.
+    tried_letters: HashMap<char, u8>,
+
+    /// Refuse guesses that use a letter from any previous guess.
+    no_letter_reuse: bool,
+
+    /// Number of unspent hints.
+    hints: u32,
+
+    /// Grant a new hint every this many guesses (0 to disable).
+    hint_regen: u32,
+
+    /// Positions of the target word revealed by hints.
+    revealed: Vec<usize>,
+
+    /// Letters revealed by hints not to be in the target word.
+    revealed_absent: Vec<char>,
+
+    /// Letters the first guess must contain, and how many of them.
+    opener_coverage: Option<(Vec<char>, usize)>,
+
+    /// Letters every guess must be made of.
+    letter_bank: Option<String>,
+}
+
+impl Game {
+    /// Start a new game with the given target word.
+    pub fn new(target: String, config: &Configuration) -> Game {
+        Game {
+            target,
+            tries: config.guess_tries,
+            history: Vec::new(),
+            tried_letters: HashMap::new(),
+            no_letter_reuse: config.no_letter_reuse,
+            hints: config.hints,
+            hint_regen: config.hint_regen,
+            revealed: Vec::new(),
+            revealed_absent: Vec::new(),
+            opener_coverage: None,
+            letter_bank: config.letter_bank.clone(),
+        }
+    }
+
+    /// Refuse a first guess that contains fewer than `required` of the given letters.
+    pub fn require_opener_coverage(&mut self, letters: Vec<char>, required: usize) {
+        self.opener_coverage = Some((letters, required));
+    }
+
+    /// The word the player is trying to guess.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Number of guess tries before the game is over.
+    pub fn tries(&self) -> u32 {
+        self.tries
+    }
+
+    /// Guesses made so far, in order.
+    pub fn history(&self) -> &[Guess] {
+        &self.history
+    }
+
+    /// Number of guesses made so far. Refused guesses are not counted.
+    pub fn guesses_made(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    /// Number of guess tries left before the game is over. Refused guesses don't use up a try.
+    pub fn remaining_tries(&self) -> u32 {
+        self.tries.saturating_sub(self.guesses_made())
+    }
+
+    /// Letters tried so far, and the number of instances of each in the target word.
+    pub fn tried_letters(&self) -> &HashMap<char, u8> {
+        &self.tried_letters
+    }
+
+    /// Strongest state known for a letter from the guesses so far: [`Correctness::Correct`] if it
+    /// has been guessed in its correct position, [`Correctness::CorrectLetter`] if it is known to
+    /// be in the word, [`Correctness::Incorrect`] if it is known not to be, and `None` if it has
+    /// not been tried.
+    pub fn letter_state(&self, letter: char) -> Option<Correctness> {
+        let strength = |c: &Correctness| match c {
+            Correctness::Correct => 2,
+            Correctness::CorrectLetter => 1,
+            Correctness::Incorrect => 0,
+        };
+
+        self.history
+            .iter()
+            .flat_map(|g| g.word.chars().zip(g.correctness.iter().copied()))
+            .filter(|(l, _)| *l == letter)
+            .map(|(_, c)| c)
+            .max_by_key(strength)
+    }
+
+    /// Snapshot of [`Game::letter_state`] for each letter from A to Z.
+    pub fn keyboard(&self) -> [Option<Correctness>; 26] {
+        std::array::from_fn(|i| self.letter_state((b'a' + i as u8) as char))
+    }
+
+    /// Letters known to be in their correct position from the guesses so far, one per position
+    /// of the target word, or `None` for positions not found yet.
+    pub fn known_greens(&self) -> Vec<Option<char>> {
+        (0..self.target.len())
+            .map(|i| {
+                self.history
+                    .iter()
+                    .find(|g| g.correctness[i] == Correctness::Correct)
+                    .map(|g| g.word.as_bytes()[i] as char)
+            })
+            .collect()
+    }
+
+    /// Check that a guess can be made, without using up a guess try. The target word is always
+    /// a valid word, even when it is not in `possible_words`.
+    pub fn check(&self, input: &str, possible_words: &HashSet<String>) -> Result<(), InvalidGuess> {
+        if input.len() != self.target.len() {
+            return Err(InvalidGuess::WrongLength(self.target.len() as u8));
+        }
+
+        if input != self.target && !possible_words.contains(input) {
+            return Err(InvalidGuess::UnknownWord);
+        }
+
+        if self.no_letter_reuse {
+            if let Some(letter) = input.chars().find(|c| self.tried_letters.contains_key(c)) {
+                return Err(InvalidGuess::LetterReused(letter));
+            }
+        }
+
+        // The target is always playable, even when a custom target is outside the bank
+        if let Some(bank) = self.letter_bank.as_ref().filter(|_| input != self.target) {
+            if let Some(letter) = input.chars().find(|c| !bank.contains(*c)) {
+                return Err(InvalidGuess::OutsideBank(letter));
+            }
+        }
+
+        if let Some((letters, required)) = &self.opener_coverage {
+            if self.history.is_empty() && analysis::coverage(input, letters) < *required {
+                return Err(InvalidGuess::WeakOpener {
+                    letters: letters.clone(),
+                    required: *required,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Score a guess without recording it, for practice once the game is over. Only the length
+    /// of the guess and the word list are checked.
+    pub fn score_uncounted(
+        &self,
+        input: &str,
+        possible_words: &HashSet<String>,
+    ) -> Result<Guess, InvalidGuess> {
+        if input.len() != self.target.len() {
+            return Err(InvalidGuess::WrongLength(self.target.len() as u8));
+        }
+
+        if input != self.target && !possible_words.contains(input) {
+            return Err(InvalidGuess::UnknownWord);
+        }
+
+        Ok(Guess {
+            word: input.to_string(),
+            correctness: evaluate_guess(input, &self.target),
+        })
+    }
+
+    /// Score a guess and record it in the history. The guess is expected to have passed
+    /// [`Game::check`].
+    pub fn guess(&mut self, input: &str) -> &Guess {
+        for letter in input.chars() {
+            let count = self.target.chars().filter(|c| *c == letter).count();
+            self.tried_letters.entry(letter).or_insert(count as u8);
+        }
+
+        self.history.push(Guess {
+            word: input.to_string(),
+            correctness: evaluate_guess(input, &self.target),
+        });
+
+        // Regenerate a hint every `hint_regen` guesses
+        if self.hint_regen > 0
+            && self.guesses_made().is_multiple_of(self.hint_regen)
+            && self.hints < MAX_REGENERATED_HINTS
+        {
+            self.hints += 1;
+        }
+
+        self.history.last().unwrap()
+    }
+
+    /// Number of unspent hints.
+    pub fn hints(&self) -> u32 {
+        self.hints
+    }
+
+    /// Spend a hint to reveal the first letter of the target word that has neither been guessed
+    /// in its correct position nor revealed by an earlier hint. Returns `None` when no hints are
+    /// left, or when there is nothing left to reveal.
+    pub fn hint(&mut self) -> Option<Hint> {
+        if self.hints == 0 {
+            return None;
+        }
+
+        let position = (0..self.target.len()).find(|i| {
+            !self.revealed.contains(i)
+                && !self
+                    .history
+                    .iter()
+                    .any(|g| g.correctness[*i] == Correctness::Correct)
+        })?;
+
+        self.hints -= 1;
+        self.revealed.push(position);
+
+        Some(Hint {
+            position,
+            letter: self.target.as_bytes()[position] as char,
+        })
+    }
+
+    /// Spend a hint to reveal the first letter from A to Z that is not in the target word, and
+    /// has neither been tried nor revealed by an earlier hint. Returns `None` when no hints are
+    /// left, or when there is nothing left to reveal.
+    pub fn absent_hint(&mut self) -> Option<char> {
+        if self.hints == 0 {
+            return None;
+        }
+
+        let letter = ('a'..='z').find(|l| {
+            !self.target.contains(*l)
+                && !self.tried_letters.contains_key(l)
+                && !self.revealed_absent.contains(l)
+        })?;
+
+        self.hints -= 1;
+        self.revealed_absent.push(letter);
+
+        Some(letter)
+    }
+
+    /// Hints left, and what hints revealed so far.
+    pub fn hint_state(&self) -> HintState {
+        HintState {
+            hints: self.hints,
+            revealed: self.revealed.clone(),
+            revealed_absent: self.revealed_absent.clone(),
+        }
+    }
+
+    /// Restore the hints of a resumed game, once its guesses have been replayed. Hints
+    /// regenerated by replaying the guesses are replaced, and hints already spent can't be
+    /// spent again.
+    pub fn restore_hints(&mut self, state: HintState) {
+        self.hints = state.hints;
+        self.revealed = state.revealed;
+        self.revealed_absent = state.revealed_absent;
+    }
+
+    /// Spend a hint of the given type, describing what it reveals. Returns `None` when no hints
+    /// are left, or when there is nothing left to reveal.
+    pub fn reveal(&mut self, hint_type: HintType) -> Option<String> {
+        match hint_type {
+            HintType::Green => self
+                .hint()
+                .map(|hint| format!("Letter {} is '{}'.", hint.position + 1, hint.letter)),
+            HintType::Absent => self
+                .absent_hint()
+                .map(|letter| format!("The letter '{}' is not in the word.", letter)),
+        }
+    }
+
+    /// Result of the game, once it is over.
+    pub fn result(&self) -> Option<GameResult> {
+        if self.history.last().is_some_and(|g| g.word == self.target) {
+            Some(GameResult::Success)
+        } else if self.remaining_tries() == 0 {
+            Some(GameResult::Failure)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Words accepted as guesses in tests.
+    fn words(words: &[&str]) -> HashSet<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    /// Check a guess, and record it if it is accepted, as the game loop does.
+    fn play(game: &mut Game, input: &str, words: &HashSet<String>) -> Result<(), InvalidGuess> {
+        game.check(input, words)?;
+        game.guess(input);
+        Ok(())
+    }
+
+    #[test]
+    fn refused_guesses_leave_the_game_unchanged() {
+        let config = Configuration::default();
+        let words = words(&["crane", "slate"]);
+        let mut game = Game::new(String::from("slate"), &config);
+        play(&mut game, "crane", &words).ok().unwrap();
+
+        let history: Vec<String> = game.history().iter().map(|g| g.word.clone()).collect();
+        let tried = game.tried_letters().clone();
+
+        assert!(matches!(
+            play(&mut game, "cranes", &words),
+            Err(InvalidGuess::WrongLength(5))
+        ));
+        assert!(matches!(
+            play(&mut game, "xxxxx", &words),
+            Err(InvalidGuess::UnknownWord)
+        ));
+
+        assert_eq!(game.guesses_made(), 1);
+        let after: Vec<String> = game.history().iter().map(|g| g.word.clone()).collect();
+        assert_eq!(after, history);
+        assert_eq!(game.tried_letters(), &tried);
+    }
+
+    #[test]
+    fn mixed_valid_and_invalid_guesses() {
+        let config = Configuration::default();
+        let words = words(&["crane", "slate", "blimp"]);
+        let mut game = Game::new(String::from("slate"), &config);
+
+        let results: Vec<bool> = ["crane", "crab", "zzzzz", "blimp", "slate"]
+            .iter()
+            .map(|input| play(&mut game, input, &words).is_ok())
+            .collect();
+
+        assert_eq!(results, [true, false, false, true, true]);
+        let history: Vec<&str> = game.history().iter().map(|g| g.word.as_str()).collect();
+        assert_eq!(history, ["crane", "blimp", "slate"]);
+        assert_eq!(game.result(), Some(GameResult::Success));
+    }
+
+    #[test]
+    fn refusals_do_not_use_up_tries() {
+        let config = Configuration {
+            guess_tries: 2,
+            ..Configuration::default()
+        };
+        let words = words(&["crane"]);
+        let mut game = Game::new(String::from("slate"), &config);
+
+        for _ in 0..10 {
+            assert!(play(&mut game, "crab", &words).is_err());
+        }
+        assert_eq!(game.remaining_tries(), 2);
+        assert_eq!(game.result(), None);
+
+        play(&mut game, "crane", &words).ok().unwrap();
+        assert_eq!(game.remaining_tries(), 1);
+    }
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(Command::parse("quit", false), Some(Command::Quit));
+        assert_eq!(Command::parse("hint", false), Some(Command::Hint));
+        assert_eq!(
+            Command::parse("opener s", true),
+            Some(Command::Opener(Some('s')))
+        );
+        assert_eq!(
+            Command::parse("opener st", true),
+            Some(Command::Opener(None))
+        );
+        assert_eq!(Command::parse("opener s", false), None);
+        assert_eq!(Command::parse("crane", true), None);
+    }
+
+    #[test]
+    fn reused_letters_are_refused() {
+        let config = Configuration {
+            no_letter_reuse: true,
+            ..Configuration::default()
+        };
+        let words = words(&["crane", "slate", "blimp"]);
+        let mut game = Game::new(String::from("fjord"), &config);
+        play(&mut game, "crane", &words).ok().unwrap();
+
+        assert!(matches!(
+            play(&mut game, "slate", &words),
+            Err(InvalidGuess::LetterReused('a'))
+        ));
+        assert!(play(&mut game, "blimp", &words).is_ok());
+    }
+
+    #[test]
+    fn glyphs_round_trip() {
+        for correctness in [
+            Correctness::Correct,
+            Correctness::CorrectLetter,
+            Correctness::Incorrect,
+        ] {
+            assert_eq!(
+                Correctness::from_glyph(correctness.glyph()),
+                Ok(correctness)
+            );
+            assert_eq!(
+                Correctness::from_glyph(correctness.emoji()),
+                Ok(correctness)
+            );
+            assert_eq!(
+                Correctness::try_from(correctness.glyph().to_ascii_uppercase()),
+                Ok(correctness)
+            );
+        }
+        assert_eq!(Correctness::from_glyph('⬜'), Ok(Correctness::Incorrect));
+        assert_eq!(
+            parse_feedback("gyx🟩⬛"),
+            Ok(vec![
+                Correctness::Correct,
+                Correctness::CorrectLetter,
+                Correctness::Incorrect,
+                Correctness::Correct,
+                Correctness::Incorrect,
+            ])
+        );
+    }
+
+    #[test]
+    fn unknown_glyphs_are_refused() {
+        assert_eq!(Correctness::from_glyph('q'), Err(InvalidGlyph('q')));
+        assert_eq!(parse_feedback("gyq"), Err(InvalidGlyph('q')));
+    }
+
+    #[test]
+    fn hints_regenerate_up_to_the_cap() {
+        let config = Configuration {
+            guess_tries: 10,
+            hints: 0,
+            hint_regen: 2,
+            ..Configuration::default()
+        };
+        let mut game = Game::new(String::from("slate"), &config);
+
+        let hints: Vec<u32> = (0..8)
+            .map(|_| {
+                game.guess("crane");
+                game.hints()
+            })
+            .collect();
+        assert_eq!(MAX_REGENERATED_HINTS, 3);
+        assert_eq!(hints, [0, 1, 1, 2, 2, 3, 3, 3]);
+
+        let hint = game.hint().unwrap();
+        assert_eq!((hint.position, hint.letter), (0, 's'));
+        assert_eq!(game.hints(), MAX_REGENERATED_HINTS - 1);
+    }
+
+    #[test]
+    fn target_outside_the_word_list_can_win() {
+        let config = Configuration::default();
+        let words = words(&["crane"]);
+        let mut game = Game::new(String::from("qajaq"), &config);
+
+        assert!(play(&mut game, "qajaq", &words).is_ok());
+        assert_eq!(game.result(), Some(GameResult::Success));
+    }
+
+    #[test]
+    fn case_sensitive_words_are_distinct() {
+        let config = Configuration {
+            case_sensitive: true,
+            ..Configuration::default()
+        };
+        let words = words(&["Crane", "slate"]);
+        let mut game = Game::new(String::from("slate"), &config);
+
+        assert!(play(&mut game, "Crane", &words).is_ok());
+        assert!(matches!(
+            play(&mut game, "crane", &words),
+            Err(InvalidGuess::UnknownWord)
+        ));
+    }
+
+    #[test]
+    fn letter_state_keeps_the_strongest_state() {
+        let mut game = Game::new(String::from("slate"), &Configuration::default());
+        game.guess("crane");
+        game.guess("least");
+
+        assert_eq!(game.letter_state('c'), Some(Correctness::Incorrect));
+        assert_eq!(game.letter_state('l'), Some(Correctness::CorrectLetter));
+        assert_eq!(game.letter_state('s'), Some(Correctness::CorrectLetter));
+        assert_eq!(game.letter_state('z'), None);
+
+        // Found in place before and after being found out of place
+        assert_eq!(game.letter_state('e'), Some(Correctness::Correct));
+        game.guess("stale");
+        assert_eq!(game.letter_state('s'), Some(Correctness::Correct));
+    }
+
+    #[test]
+    fn absent_hints_are_untried_letters_outside_the_word() {
+        let config = Configuration {
+            hints: 30,
+            ..Configuration::default()
+        };
+        let mut game = Game::new(String::from("slate"), &config);
+        game.guess("crane");
+        game.guess("bumpy");
+
+        let letters: Vec<char> = std::iter::from_fn(|| game.absent_hint()).collect();
+        assert!(!letters.is_empty());
+        for letter in &letters {
+            assert!(!game.target().contains(*letter), "{}", letter);
+            assert!(!game.tried_letters().contains_key(letter), "{}", letter);
+        }
+        assert_eq!(
+            letters.len(),
+            ('a'..='z')
+                .filter(|l| !"slatecrnbumpy".contains(*l))
+                .count()
+        );
+    }
+
+    #[test]
+    fn guesses_made_and_remaining_tries_add_up() {
+        let config = Configuration {
+            guess_tries: 3,
+            ..Configuration::default()
+        };
+        let words = words(&["crane", "blimp", "fjord"]);
+        let mut game = Game::new(String::from("slate"), &config);
+        assert_eq!((game.guesses_made(), game.remaining_tries()), (0, 3));
+
+        play(&mut game, "crane", &words).ok().unwrap();
+        assert_eq!((game.guesses_made(), game.remaining_tries()), (1, 2));
+
+        assert!(play(&mut game, "cran", &words).is_err());
+        assert_eq!((game.guesses_made(), game.remaining_tries()), (1, 2));
+
+        play(&mut game, "blimp", &words).ok().unwrap();
+        play(&mut game, "fjord", &words).ok().unwrap();
+        assert_eq!((game.guesses_made(), game.remaining_tries()), (3, 0));
+        assert_eq!(game.result(), Some(GameResult::Failure));
+    }
+
+    #[test]
+    fn one_letter_guesses_are_the_wrong_length() {
+        let mut game = Game::new(String::from("slate"), &Configuration::default());
+        for input in ["y", "n"] {
+            assert!(matches!(
+                play(&mut game, input, &words(&["y", "n"])),
+                Err(InvalidGuess::WrongLength(5))
+            ));
+        }
+        assert_eq!(game.guesses_made(), 0);
+    }
+}
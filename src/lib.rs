@@ -0,0 +1,13 @@
+//! Wordle in the terminal.
+//!
+//! The binary in `main.rs` is thin glue over these modules, which can also be used to drive a game
+//! from another interface.
+
+pub mod analysis;
+pub mod cli;
+pub mod game;
+pub mod render;
+pub mod resume;
+pub mod sha256;
+pub mod versus;
+pub mod words;
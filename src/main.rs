@@ -1,229 +1,534 @@
-use colored::{self, Colorize};
+use std::io::IsTerminal;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use rust_wordle::analysis;
+use rust_wordle::cli::{Configuration, Input, MenuChoice};
+use rust_wordle::game::{self, Command, Game, GameResult, Guess, Session};
+use rust_wordle::render::{self, Style};
+use rust_wordle::resume::ResumeCode;
+use rust_wordle::versus::{self, Finish, Host, Join};
+use rust_wordle::words::{self, WordLists};
 
 fn main() {
-    wordle();
-}
+    let mut config = match Configuration::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => exit_with_error(&e),
+    };
 
-/// Correctness of a letter used in a position.
-#[derive(PartialEq)]
-enum Correctness {
-    /// The correct letter is in the correct position.
-    Correct,
+    if config.no_color {
+        colored::control::set_override(false);
+    }
 
-    /// A correct letter is in an incorrect position.
-    CorrectLetter,
+    if config.truecolor && render::detect_truecolor() == Some(false) {
+        eprintln!("warning: the terminal does not report 24-bit color support, using named colors");
+        config.truecolor = false;
+    }
 
-    /// An incorrect (and unused) letter is used in an incorrect position.
-    Incorrect,
-}
+    if config.analyze {
+        analyze(&config);
+        return;
+    }
+
+    let resume = config.resume_code.as_deref().map(|code| {
+        let code = ResumeCode::decode(code).unwrap_or_else(|e| exit_with_error(&e));
+        config.seed = Some(code.seed);
+        config.guess_tries = code.tries;
+        code
+    });
+
+    let words = match WordLists::load(&config) {
+        Ok(words) => words,
+        Err(e) => exit_with_error(&e),
+    };
+
+    // The resumed game is the first one played, and must be played on the same word
+    if let Some(code) = &resume {
+        let target = words.pick_target(&config, &mut StdRng::seed_from_u64(code.seed), None);
+        if !code.matches(&target) {
+            exit_with_error(
+                "the resume code was made for a different word; resume with the options the game was started with (such as '--target', '--hardest', or word filters)",
+            );
+        }
+    }
+    let mut resume = resume;
+
+    // Confirm that a custom list of allowed guesses loaded as expected
+    if config.verbose && config.allowed_file.is_some() {
+        println!(
+            "Answers: {}, Allowed guesses: {}",
+            words.answers.len(),
+            words.allowed.len()
+        );
+    }
+
+    if config.solve_from {
+        solve_from(&config, &words);
+        return;
+    }
 
-/// Result of the game that was played.
-enum GameResult {
-    /// The player won.
-    Success,
+    // Stop once everything has loaded, without revealing the word that would be played
+    if config.dry_run {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let target = words.pick_target(&config, &mut StdRng::seed_from_u64(seed), None);
+        println!(
+            "Dry run: picked a {}-letter word from a pool of {}, with {} valid guesses, {} tries, {:?} selection, and {:?} validity.",
+            target.len(),
+            words.pool.len(),
+            words.valid_guesses(config.validity).len(),
+            config.guess_tries,
+            config.selection,
+            config.validity
+        );
+        return;
+    }
+
+    let mut input = match &config.guesses_file {
+        Some(path) => Input::from_file(path).unwrap_or_else(|e| exit_with_error(&e)),
+        None => Input::stdin(),
+    };
+
+    if config.host.is_some() || config.join.is_some() {
+        race(&config, &words, &mut input);
+        return;
+    }
+
+    let mut session = Session::default();
+
+    // Target word of the last game, for word ladders
+    let mut previous: Option<String> = None;
+
+    loop {
+        let seed = config.seed.map_or_else(rand::random, |seed| {
+            seed.wrapping_add(session.played as u64)
+        });
+
+        let resumed = resume.take();
+
+        // Stop without a result when the game is quit
+        let ladder = previous.as_deref();
+        let (game, result) = wordle(&config, &words, &mut input, seed, resumed, ladder);
+        let Some(result) = result else {
+            break;
+        };
+        session.record(&result);
+        previous = Some(game.target().to_string());
+
+        // Non-interactive runs play a single game
+        if !config.menu || !std::io::stdin().is_terminal() {
+            break;
+        }
+
+        if !menu(&mut input, &session) {
+            break;
+        }
+        println!();
+    }
+}
 
-    /// The player lost.
-    Failure,
+/// Print how often each letter is at each position. A custom dictionary is read one word at a
+/// time rather than loaded, so that dictionaries too large to hold in memory can be analyzed.
+fn analyze(config: &Configuration) {
+    let length = config.guess_letters as usize;
+    let frequencies = match &config.allowed_file {
+        Some(path) => analysis::positional_frequencies(
+            words::read_words(path, config).unwrap_or_else(|e| exit_with_error(&e)),
+            length,
+        ),
+        None => {
+            let words = WordLists::load(config).unwrap_or_else(|e| exit_with_error(&e));
+            analysis::positional_frequencies(&words.answers, length)
+        }
+    };
+    print!("{}", render::matrix_csv(&frequencies));
 }
 
-/// Wordle configuration.
-struct Configuration {
-    /// Number of guess tries before the game is over.
-    guess_tries: u32,
+/// Print the words that could still be the answer on a board played elsewhere, and the best next
+/// guess.
+fn solve_from(config: &Configuration, words: &WordLists) {
+    const SHOWN: usize = 20;
+
+    let board = config.board.as_deref().unwrap_or("");
+    let board = game::parse_board(board, config.guess_letters as usize)
+        .unwrap_or_else(|e| exit_with_error(&e));
+    let candidates = analysis::candidates(&words.pool, &board);
 
-    /// Number of letters in the word to be guessed.
-    guess_letters: u8,
+    match candidates[..] {
+        [] => {
+            println!("No possible answer matches this board. Check the feedback for a mistake.");
+            return;
+        }
+        [word] => {
+            println!("The word is: {}", word);
+            return;
+        }
+        _ => {}
+    }
+
+    print!("{} possible answers: ", candidates.len());
+    print!("{}", candidates[..candidates.len().min(SHOWN)].join(", "));
+    if candidates.len() > SHOWN {
+        print!(", and {} more", candidates.len() - SHOWN);
+    }
+    println!();
+
+    let guesses = words.valid_guesses(config.validity);
+    if let Some(guess) = analysis::best_guess(guesses, &candidates) {
+        println!("Best next guess: {}", guess);
+    }
 }
 
-fn wordle() {
-    // Define program config
-    let config = Configuration {
-        guess_tries: 6,
-        guess_letters: 5,
+/// Play a single game racing other players, as the host or a joined player.
+fn race(config: &Configuration, words: &WordLists, input: &mut Input) {
+    let finish = |game: &Game, result: Option<GameResult>| {
+        result.map(|result| Finish {
+            won: result == GameResult::Success,
+            guesses: game.guesses_made(),
+        })
     };
 
-    let possible_words: Vec<String>;
+    if let Some(address) = &config.host {
+        let seed = config.seed.unwrap_or_else(rand::random);
+
+        // Commit to the word the seed picks, to reveal it once everyone is done
+        let target = words.pick_target(config, &mut StdRng::seed_from_u64(seed), None);
+        let salt = format!("{:016x}", rand::random::<u64>());
+        let commitment = versus::commitment(&target, &salt);
+
+        let host =
+            Host::start(address, seed, commitment.clone()).unwrap_or_else(|e| exit_with_error(&e));
+        println!(
+            "Hosting on {}. Players who join before you finish race on the same word.",
+            address
+        );
+        println!("Word commitment: {}", commitment);
+
+        let (game, result) = wordle(config, words, input, seed, None, None);
+        println!("Waiting for the other players to finish...");
+        let ranking = host.finish(finish(&game, result), &target, &salt);
 
-    {
-        let all_words: &str = include_str!("wordle-nyt-answers-alphabetical.txt");
-        possible_words = words_list(all_words, &config);
+        println!();
+        println!("Revealed word: {}, salt: {}", target, salt);
+        print!("{}", ranking);
+    } else if let Some(address) = &config.join {
+        let (join, seed, commitment) =
+            Join::connect(address).unwrap_or_else(|e| exit_with_error(&e));
+        println!("Joined the race on {}.", address);
+        println!("Word commitment: {}", commitment);
+
+        let (game, result) = wordle(config, words, input, seed, None, None);
+        println!("Waiting for the other players to finish...");
+        let reveal = join
+            .finish(finish(&game, result))
+            .unwrap_or_else(|e| exit_with_error(&e));
+
+        println!();
+        println!("Revealed word: {}, salt: {}", reveal.target, reveal.salt);
+        if versus::commitment(&reveal.target, &reveal.salt) == commitment {
+            println!("The revealed word matches the commitment.");
+        } else {
+            println!("warning: the revealed word does not match the commitment!");
+        }
+        // An honest commitment can still be to another word, such as with other word lists
+        if reveal.target != game.target() {
+            println!(
+                "warning: the revealed word is not the word you played ({}); the host may have other word lists or filters",
+                game.target()
+            );
+        }
+        print!("{}", reveal.ranking);
+    }
+}
+
+/// Prompt the end-of-game menu until a choice other than showing stats is made. Returns whether
+/// to play again, or `false` to quit.
+fn menu(input: &mut Input, session: &Session) -> bool {
+    // Input typed for the game is not a menu choice
+    let discarded = input.discard_waiting();
+    if discarded > 0 {
+        println!("Ignored {} line(s) typed before the menu.", discarded);
     }
 
-    let mut result = GameResult::Failure;
+    loop {
+        print!("[p]lay again, [s]tats, [q]uit> ");
+        flush();
+
+        let Some(line) = input.read_line() else {
+            return false;
+        };
+
+        match MenuChoice::parse(&line) {
+            Some(MenuChoice::PlayAgain) => return true,
+            Some(MenuChoice::Stats) => render::print_session(session),
+            Some(MenuChoice::Quit) => return false,
+            None => println!("Please choose p, s, or q."),
+        }
+    }
+}
 
-    // Array of guessed letters in order from A to Z, and the number of instances of the letter in the target word
-    let mut target_letter_count: std::collections::HashMap<char, u8> =
-        std::collections::HashMap::new();
+/// Print an error and exit with a nonzero status.
+fn exit_with_error(error: &str) -> ! {
+    eprintln!("error: {}", error);
+    std::process::exit(2);
+}
 
-    // Possible words to be the target word to guess
+/// Play a single game, picking the target word with the given seed (and sharing letters with the
+/// `previous` target word in a word ladder). The guesses of a `resume` code are made before
+/// reading any input, and its hints are restored after them. Returns the game, and its result,
+/// or `None` if the game is quit before it is over.
+fn wordle(
+    config: &Configuration,
+    words: &WordLists,
+    input: &mut Input,
+    seed: u64,
+    resume: Option<ResumeCode>,
+    previous: Option<&str>,
+) -> (Game, Option<GameResult>) {
+    let mut rng = StdRng::seed_from_u64(seed);
 
     // The word the player is trying to guess
-    let target_word = possible_words
-        .choose(&mut rand::thread_rng())
-        .unwrap()
-        .to_string();
+    let target_word = words.pick_target(config, &mut rng, previous);
 
-    // Number of guesses made by the player during the game
-    let mut guesses = 0;
+    let mut game = Game::new(target_word, config);
+    let started = Instant::now();
 
-    print_tried_letters(&target_letter_count);
-    print!(" ");
+    if config.require_opener_coverage {
+        let letters = analysis::top_letters(&words.answers, config.opener_top);
+        game.require_opener_coverage(letters, config.opener_min);
+    }
 
-    // Game loop (break on max guesses)
-    while guesses < config.guess_tries {
-        guesses += 1;
+    // Order of the tried letters display
+    let mut keyboard = render::ALPHABET;
+    if config.scramble_keyboard {
+        keyboard.shuffle(&mut rng);
+    }
 
-        let mut correctness: std::collections::HashMap<u8, Correctness> =
-            std::collections::HashMap::new();
+    if config.legend {
+        render::print_legend(Style::new(config));
+    }
 
-        // Number of times a certain letter has been printed as contained in the target word
-        let mut letter_count: std::collections::HashMap<char, u8> =
-            std::collections::HashMap::new();
+    if let Some(letter) = config.opener_letter {
+        suggest_opener(config, words, letter);
+    }
 
+    print_keyboard(config, &game, &keyboard);
+
+    let (replay, mut resumed_hints) = match resume {
+        Some(code) => (code.guesses, Some(code.hints)),
+        None => (Vec::new(), None),
+    };
+    let mut replay = replay.into_iter();
+
+    // Game loop (break on game over)
+    let result = loop {
         // Prompt user input
-        print!("({}/{})> ", guesses, config.guess_tries);
+        print!("{}", render::prompt(config, words, &game));
         flush();
 
-        // Read user input
-        let mut input: String = text_io::read!("{}\n");
+        // Read user input, abandoning the game at the end of input as if it were quit
+        let input = match replay.next() {
+            Some(guess) => {
+                println!("{}", guess);
+                guess
+            }
+            None => {
+                // Replaying the guesses regenerates hints, so they are restored afterwards
+                if let Some(hints) = resumed_hints.take() {
+                    game.restore_hints(hints);
+                }
+                match input.read_line() {
+                    Some(line) => line,
+                    None => {
+                        println!();
+                        return (game, None);
+                    }
+                }
+            }
+        };
+
+        // Strip user input of additional whitespace
+        let input = if config.case_sensitive {
+            input.trim().to_string()
+        } else {
+            input.trim().to_lowercase()
+        };
+
+        if let Some(command) = Command::parse(&input, config.helpers) {
+            match command {
+                // Stop playing, printing a code to resume the game later
+                Command::Quit => {
+                    let code = ResumeCode::for_game(&game, seed);
+                    println!("Resume code: {}", code.encode());
+                    return (game, None);
+                }
 
-        // Strip user input of additional whitespace, including newlines
-        input = input.trim().to_lowercase().to_string();
+                // Reveal something about the word without using up a guess try
+                Command::Hint => match game.reveal(config.hint_type) {
+                    Some(hint) => println!("{} Hints left: {}", hint, game.hints()),
+                    None => println!("No hints available."),
+                },
 
-        // Catch incorrect number of letters in guess, and refund the guess try
-        if input.len() != config.guess_letters as usize {
-            guesses -= 1;
-            println!("The word is {} letters in length.", config.guess_letters);
+                // Suggest an opener starting with the given letter, without submitting it
+                Command::Opener(Some(letter)) => suggest_opener(config, words, letter),
+                Command::Opener(None) => println!("Usage: opener <letter>"),
+            }
             continue;
         }
 
-        // Catch invalid words and refund guess try
-        if !(&possible_words).into_iter().any(|w| w == &input) {
-            guesses -= 1;
-            println!("Please use a valid word.");
+        // Catch invalid guesses, and refund the guess try
+        if let Err(invalid) = game.check(&input, words.guess_set(config.validity)) {
+            println!("{}", invalid);
             continue;
         }
 
-        // Operations on input
-        for i in 0..input.chars().count() {
-            let letter = input.as_bytes()[i] as char;
+        let before = game.keyboard();
 
-            // Count letters in input
-            let count = target_word.chars().filter(|c| c == &letter).count() as i8;
-            target_letter_count.entry(letter).or_insert(count as u8);
+        let guess = game.guess(&input);
+        if config.blind {
+            render::print_guess_counts(guess);
+        } else {
+            print_guess(config, guess);
         }
 
-        // Mark correctly placed letters
-        for i in 0..input.chars().count() {
-            let letter = input.as_bytes()[i] as char;
-
-            if letter == target_word.as_bytes()[i] as char {
-                // If letter is in the correct position
-                *letter_count.entry(letter).or_insert(0) += 1;
-                correctness.entry(i as u8).or_insert(Correctness::Correct);
+        // Show what the guess changed, unless that would give away the board in blind mode
+        if config.keyboard_diff && !config.blind {
+            let diff = render::keyboard_diff(&before, &game.keyboard());
+            if !diff.is_empty() {
+                println!("{}", diff);
             }
         }
 
-        // Mark existing letters and letters not in target word
-        for i in 0..input.chars().count() {
-            let letter = input.as_bytes()[i] as char;
-
-            // Mark letters that exist in target word
-            if target_word.chars().any(|c| c == letter) {
-                // If letter exists in target word
-                if letter_count.entry(letter).or_insert(0)
-                    < target_letter_count.entry(letter).or_insert(0)
-                    && letter != target_word.as_bytes()[i] as char
-                {
-                    // Letter has not already been marked as existing (if there are more than one)
-                    *letter_count.entry(letter).or_insert(0) += 1;
-                    correctness
-                        .entry(i as u8)
-                        .or_insert(Correctness::CorrectLetter);
-                } else {
-                    // Letter has already been marked as existing (if there are more than one)
-                    correctness.entry(i as u8).or_insert(Correctness::Incorrect);
-                }
-            }
-            // Mark letters that are not in target word
-            else {
-                // If letter is not in target word
-                correctness.entry(i as u8).or_insert(Correctness::Incorrect);
-            }
+        // Note probe words, which are accepted as guesses but can never be the answer
+        if config.warn_non_answer && !words.is_answer(&input) {
+            println!("probe word (not a possible answer)");
         }
 
-        for i in 0..input.chars().count() {
-            match correctness.entry(i as u8).or_insert(Correctness::Incorrect) {
-                Correctness::Correct => {
-                    print!("{}", String::from(input.as_bytes()[i] as char).green())
-                }
-                Correctness::CorrectLetter => {
-                    print!("{}", String::from(input.as_bytes()[i] as char).blue())
-                }
-                Correctness::Incorrect => {
-                    print!("{}", String::from(input.as_bytes()[i] as char))
-                }
-            }
-        }
-        println!();
+        let result = game.result();
 
-        // Mark success and end game loop if word is guessed
-        if input == target_word {
-            result = GameResult::Success;
-            break;
+        if !matches!(result, Some(GameResult::Success)) {
+            print_keyboard(config, &game, &keyboard);
         }
 
-        // Prints tried letters (DOES NOT FLUSH, buffer is flushed on next input prompt call)
-        print_tried_letters(&target_letter_count);
-
-        // Insert space after letter list before input prompt
-        print!(" ")
+        // End game loop if word is guessed or tries ran out
+        if let Some(result) = result {
+            break result;
+        }
+    };
+    // The time is taken before studying the loss, which is not part of the recorded game
+    let time_ms = started.elapsed().as_millis();
+    println!();
+
+    // Reveal the board that was hidden during the game
+    if config.blind {
+        for guess in game.history() {
+            render::print_guess(guess, Style::new(config));
+        }
     }
-    println!("");
+
     // Finalize the game
     match result {
         GameResult::Success => {}
+        GameResult::Failure if config.study_on_loss => study(config, words, input, &game),
         GameResult::Failure => {
-            println!("The word was: {}", target_word)
+            println!("The word was: {}", game.target())
         }
     }
-}
 
-fn flush() {
-    std::io::Write::flush(&mut std::io::stdout()).unwrap();
+    render::print_game_over(config, words, &game, result, seed, time_ms);
+
+    (game, Some(result))
 }
 
-fn sanitize_word(word: &str) -> String {
-    word.trim()
-        .to_lowercase()
-        .chars()
-        .filter(|c| c.is_ascii_alphabetic())
-        .collect()
+/// Keep guessing a lost game until the word is found or `done` is entered. Study guesses are
+/// scored like any other, but are not recorded in the game, so they don't change its result.
+fn study(config: &Configuration, words: &WordLists, input: &mut Input, game: &Game) {
+    println!("Study mode: keep guessing (uncounted), or enter 'done' to reveal the word.");
+
+    let mut guesses = 0;
+    loop {
+        print!("(study)> ");
+        flush();
+
+        let Some(line) = input.read_line() else {
+            break;
+        };
+        let line = if config.case_sensitive {
+            line.trim().to_string()
+        } else {
+            line.trim().to_lowercase()
+        };
+
+        if line == "done" {
+            break;
+        }
+
+        match game.score_uncounted(&line, words.guess_set(config.validity)) {
+            Ok(guess) => {
+                print_guess(config, &guess);
+                guesses += 1;
+                if guess.word == game.target() {
+                    let noun = if guesses == 1 { "guess" } else { "guesses" };
+                    println!("Found it after {} study {}.", guesses, noun);
+                    return;
+                }
+            }
+            Err(invalid) => println!("{}", invalid),
+        }
+    }
+
+    println!("The word was: {}", game.target());
 }
 
-fn words_list(all_words: &str, config: &Configuration) -> Vec<String> {
-    all_words
-        .split('\n')
-        .skip(2)
-        .map(sanitize_word)
-        .filter(|line| line.len() == config.guess_letters as usize)
-        .collect()
+/// Print a suggested opener starting with the given letter.
+fn suggest_opener(config: &Configuration, words: &WordLists, letter: char) {
+    let top = analysis::top_letters(&words.answers, config.opener_top);
+    match analysis::best_opener(words.valid_guesses(config.validity), letter, &top) {
+        Some(opener) => println!("Suggested opener: {}", opener),
+        None => println!("No valid word starts with '{}'.", letter),
+    }
 }
 
-/// Print list of tried letters from A to Z
-fn print_tried_letters(target_letter_count: &std::collections::HashMap<char, u8>) {
-    let alphabet = [
-        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r',
-        's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
-    ];
-    for letter in alphabet {
-        if target_letter_count.contains_key(&letter) && target_letter_count[&letter] == 0 {
-            print!("{}", String::from(letter).red());
-        } else if target_letter_count.contains_key(&letter) && target_letter_count[&letter] > 0 {
-            print!("{}", String::from(letter).blue())
-        } else {
-            print!("{}", String::from(letter));
+/// Print a scored guess, in block letters if configured and the terminal is wide enough.
+fn print_guess(config: &Configuration, guess: &Guess) {
+    let style = Style::new(config);
+    if config.big_text {
+        // Terminal width, as exported by the shell
+        let columns = std::env::var("COLUMNS")
+            .ok()
+            .and_then(|c| c.parse().ok())
+            .unwrap_or(80);
+
+        if let Some(big) = render::big_guess(guess, columns, style) {
+            print!("{}", big);
+            return;
         }
     }
+
+    render::print_guess(guess, style);
+}
+
+/// Print the state of the tried letters ahead of the input prompt.
+fn print_keyboard(config: &Configuration, game: &Game, keyboard: &[char]) {
+    if config.letters_left {
+        render::print_letters_left(game.tried_letters());
+    }
+
+    // Prints tried letters (DOES NOT FLUSH, buffer is flushed on next input prompt call). Their
+    // state is hidden in blind mode, as it would give away which letters are in the word.
+    let style = Style::new(config);
+    if config.blind {
+        render::print_tried_letters(|_| None, keyboard, style);
+    } else {
+        render::print_tried_letters(|letter| game.letter_state(letter), keyboard, style);
+    }
+
+    // Insert space after letter list before input prompt
+    print!(" ")
+}
+
+fn flush() {
+    std::io::Write::flush(&mut std::io::stdout()).unwrap();
 }
@@ -0,0 +1,644 @@
+use std::collections::HashMap;
+
+use colored::{self, Colorize};
+
+use crate::analysis;
+use crate::cli::Configuration;
+use crate::game::{Correctness, Game, GameResult, Guess, Session};
+use crate::sha256::sha256_hex;
+use crate::words::WordLists;
+
+/// How letters and tiles are rendered in the terminal.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Style {
+    /// Color letters by their correctness. Without color, the correctness is encoded in the
+    /// letters instead.
+    pub color: bool,
+
+    /// Color tiles with the exact RGB colors of [`tile_rgb`], for terminals that support 24-bit
+    /// color. Named colors are used otherwise, as they work in any terminal.
+    pub truecolor: bool,
+
+    /// Guesses keep the case they were typed in, so case can't encode correctness.
+    pub case_sensitive: bool,
+}
+
+impl Style {
+    /// Style of a configured game. Color is used unless disabled through `--no-color` or the
+    /// `NO_COLOR` environment variable.
+    pub fn new(config: &Configuration) -> Style {
+        Style {
+            color: !config.no_color && colored::control::SHOULD_COLORIZE.should_colorize(),
+            truecolor: config.truecolor,
+            case_sensitive: config.case_sensitive,
+        }
+    }
+}
+
+/// Whether the terminal reports 24-bit color support through `COLORTERM`, or `None` if it
+/// doesn't say.
+pub fn detect_truecolor() -> Option<bool> {
+    let colorterm = std::env::var("COLORTERM").ok()?;
+    Some(colorterm == "truecolor" || colorterm == "24bit")
+}
+
+/// Color text by the correctness of a letter in the word: green when correct, and blue when in
+/// the word. Other text is left as it is.
+fn paint(text: &str, correctness: Correctness, style: Style) -> String {
+    if correctness == Correctness::Incorrect {
+        return String::from(text);
+    }
+
+    if style.truecolor {
+        let (r, g, b) = tile_rgb(correctness);
+        return text.truecolor(r, g, b).to_string();
+    }
+
+    match correctness {
+        Correctness::Correct => text.green().to_string(),
+        _ => text.blue().to_string(),
+    }
+}
+
+/// Render a letter of a guess, colored by its correctness.
+///
+/// When color is disabled (through `--no-color` or the `NO_COLOR` environment variable), the
+/// correctness is encoded in the letter instead: correct letters are uppercase (or in braces in
+/// case-sensitive games), letters in an incorrect position are bracketed, and incorrect letters
+/// are left as they are.
+pub fn render_letter(letter: char, correctness: Correctness, style: Style) -> String {
+    if !style.color {
+        return match correctness {
+            Correctness::Correct if style.case_sensitive => {
+                format!("{{{}}}", letter)
+            }
+            Correctness::Correct => letter.to_uppercase().to_string(),
+            Correctness::CorrectLetter => format!("[{}]", letter),
+            Correctness::Incorrect => String::from(letter),
+        };
+    }
+
+    paint(&String::from(letter), correctness, style)
+}
+
+/// Print what the rendering of each correctness means, using the same rendering as guesses.
+pub fn print_legend(style: Style) {
+    println!(
+        "{}=correct spot, {}=in word, {}=not in word",
+        render_letter('a', Correctness::Correct, style),
+        render_letter('a', Correctness::CorrectLetter, style),
+        render_letter('a', Correctness::Incorrect, style)
+    );
+}
+
+/// Hex color of a tile in images of the board, matching the terminal colors.
+pub fn tile_color(correctness: Correctness) -> &'static str {
+    match correctness {
+        Correctness::Correct => "#6aaa64",
+        Correctness::CorrectLetter => "#4a7fd4",
+        Correctness::Incorrect => "#787c7e",
+    }
+}
+
+/// RGB color of a tile, the same as [`tile_color`], for coloring terminal text.
+pub fn tile_rgb(correctness: Correctness) -> (u8, u8, u8) {
+    match correctness {
+        Correctness::Correct => (0x6a, 0xaa, 0x64),
+        Correctness::CorrectLetter => (0x4a, 0x7f, 0xd4),
+        Correctness::Incorrect => (0x78, 0x7c, 0x7e),
+    }
+}
+
+/// Render the board as an SVG image, one row of tiles per guess.
+pub fn svg_board(history: &[Guess]) -> String {
+    const TILE: usize = 60;
+    const GAP: usize = 6;
+
+    let columns = history.first().map_or(0, |g| g.correctness.len());
+    let width = columns * (TILE + GAP) + GAP;
+    let height = history.len() * (TILE + GAP) + GAP;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        width, height
+    );
+
+    for (row, guess) in history.iter().enumerate() {
+        for (column, (letter, correctness)) in
+            guess.word.chars().zip(&guess.correctness).enumerate()
+        {
+            let x = GAP + column * (TILE + GAP);
+            let y = GAP + row * (TILE + GAP);
+            svg += &format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                x,
+                y,
+                TILE,
+                TILE,
+                tile_color(*correctness)
+            );
+            svg += &format!(
+                "  <text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"32\" font-weight=\"bold\" fill=\"#ffffff\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x + TILE / 2,
+                y + TILE / 2,
+                letter.to_ascii_uppercase()
+            );
+        }
+    }
+
+    svg += "</svg>\n";
+    svg
+}
+
+/// Render the board as a shareable grid of tile emoji, under a "Wordle 3/6" style header (with
+/// an X for a lost game). The letters are left out, so the grid does not give away the word.
+pub fn share_grid(history: &[Guess], tries: u32, won: bool) -> String {
+    let score = if won {
+        history.len().to_string()
+    } else {
+        String::from("X")
+    };
+
+    let mut grid = format!("Wordle {}/{}\n", score, tries);
+    for guess in history {
+        grid.push('\n');
+        grid.extend(guess.correctness.iter().map(|c| c.emoji()));
+    }
+    grid
+}
+
+/// Describe each row of the share grid in words, such as `Row 1: 1 correct, 2 present`, for
+/// screen readers. Like the grid, it does not give away the word.
+pub fn share_alt_text(history: &[Guess]) -> String {
+    history
+        .iter()
+        .enumerate()
+        .map(|(row, guess)| {
+            let (correct, present) = guess.counts();
+            format!("Row {}: {} correct, {} present", row + 1, correct, present)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A line of stats to go under the share grid: how many positions had been found in their
+/// correct place by each guess, such as `Greens by guess: 1, 3, 3, 5`, or `None` if no guesses
+/// were made. Only counts are given, so it does not give away the word.
+pub fn share_stats(history: &[Guess]) -> Option<String> {
+    let columns = history.first()?.correctness.len();
+    let mut found = vec![false; columns];
+    let counts: Vec<String> = history
+        .iter()
+        .map(|guess| {
+            for (found, correctness) in found.iter_mut().zip(&guess.correctness) {
+                *found |= *correctness == Correctness::Correct;
+            }
+            found.iter().filter(|f| **f).count().to_string()
+        })
+        .collect();
+
+    Some(format!("Greens by guess: {}", counts.join(", ")))
+}
+
+/// Describe which guess first found each position in its correct place, such as `Position 1
+/// found on guess 2, position 2 on guess 1`. Positions never found are left out.
+pub fn review(history: &[Guess]) -> String {
+    let columns = history.first().map_or(0, |g| g.correctness.len());
+    let found: Vec<(usize, usize)> = (0..columns)
+        .filter_map(|i| {
+            let guess = history
+                .iter()
+                .position(|g| g.correctness[i] == Correctness::Correct)?;
+            Some((i + 1, guess + 1))
+        })
+        .collect();
+
+    found
+        .iter()
+        .enumerate()
+        .map(|(n, (position, guess))| {
+            if n == 0 {
+                format!("Position {} found on guess {}", position, guess)
+            } else {
+                format!("position {} on guess {}", position, guess)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Render a one-line JSON summary of a finished game, for leaderboards and other tools. The
+/// schema is stable, and changes to it bump `version`:
+///
+/// - `version`: version of the schema, currently 1.
+/// - `mode`: `solo`, `host`, or `join`.
+/// - `seed`: seed the target word was picked with.
+/// - `target_sha256`: SHA-256 hash of the target word, in hex, so that it is not given away.
+/// - `guesses`: the guessed words, in order.
+/// - `tries`: number of guess tries the game allowed.
+/// - `result`: `success` or `failure`.
+/// - `time_ms`: time taken to play the game, in milliseconds.
+pub fn report_json(
+    game: &Game,
+    result: GameResult,
+    mode: &str,
+    seed: u64,
+    time_ms: u128,
+) -> String {
+    let guesses: Vec<String> = game
+        .history()
+        .iter()
+        .map(|g| format!("\"{}\"", g.word))
+        .collect();
+    let result = match result {
+        GameResult::Success => "success",
+        GameResult::Failure => "failure",
+    };
+
+    format!(
+        "{{\"version\":1,\"mode\":\"{}\",\"seed\":{},\"target_sha256\":\"{}\",\"guesses\":[{}],\"tries\":{},\"result\":\"{}\",\"time_ms\":{}}}",
+        mode,
+        seed,
+        sha256_hex(game.target().as_bytes()),
+        guesses.join(","),
+        game.tries(),
+        result,
+        time_ms
+    )
+}
+
+/// Print what was asked for at the end of a game: the review, letter coverage, position
+/// difficulty, share grid, anagrams, and report, and write the SVG board.
+pub fn print_game_over(
+    config: &Configuration,
+    words: &WordLists,
+    game: &Game,
+    result: GameResult,
+    seed: u64,
+    time_ms: u128,
+) {
+    if config.review && result == GameResult::Success {
+        println!("{}.", review(game.history()));
+    }
+
+    if let Some(goal) = config.coverage_goal {
+        let tried = game.tried_letters().len();
+        let status = if tried >= goal { "met" } else { "not met" };
+        println!(
+            "Tried {} different letters, goal of {} {}.",
+            tried, goal, status
+        );
+    }
+
+    if config.heatmap {
+        let frequencies = analysis::positional_frequencies(&words.answers, game.target().len());
+        let difficulties = analysis::position_difficulty(game.target(), &frequencies);
+        println!("{}", game.target());
+        println!("{} position difficulty", heatmap_bar(&difficulties));
+    }
+
+    if config.share {
+        let won = matches!(result, GameResult::Success);
+        println!();
+        println!("{}", share_grid(game.history(), game.tries(), won));
+        if config.share_stats {
+            if let Some(stats) = share_stats(game.history()) {
+                println!("{}", stats);
+            }
+        }
+        if config.alt_text {
+            println!();
+            println!("{}", share_alt_text(game.history()));
+        }
+    }
+
+    if let Some(path) = &config.svg {
+        if let Err(e) = std::fs::write(path, svg_board(game.history())) {
+            eprintln!("warning: could not write '{}': {}", path, e);
+        }
+    }
+
+    if config.show_anagrams {
+        let anagrams = words.anagrams(game.target());
+        if anagrams.is_empty() {
+            println!("No anagrams of {}.", game.target());
+        } else {
+            println!("Anagrams of {}: {}", game.target(), anagrams.join(", "));
+        }
+    }
+
+    if config.report {
+        let mode = match (&config.host, &config.join) {
+            (Some(_), _) => "host",
+            (_, Some(_)) => "join",
+            _ => "solo",
+        };
+        println!("{}", report_json(game, result, mode, seed, time_ms));
+    }
+}
+
+/// Print a scored guess on its own line.
+pub fn print_guess(guess: &Guess, style: Style) {
+    for (letter, correctness) in guess.word.chars().zip(&guess.correctness) {
+        print!("{}", render_letter(letter, *correctness, style));
+    }
+    println!();
+}
+
+/// Block font for [`big_guess`], five rows per letter from A to Z.
+const BIG_FONT: [[&str; 5]; 26] = [
+    [" ### ", "#   #", "#####", "#   #", "#   #"],
+    ["#### ", "#   #", "#### ", "#   #", "#### "],
+    [" ####", "#    ", "#    ", "#    ", " ####"],
+    ["#### ", "#   #", "#   #", "#   #", "#### "],
+    ["#####", "#    ", "#### ", "#    ", "#####"],
+    ["#####", "#    ", "#### ", "#    ", "#    "],
+    [" ####", "#    ", "#  ##", "#   #", " ####"],
+    ["#   #", "#   #", "#####", "#   #", "#   #"],
+    ["#####", "  #  ", "  #  ", "  #  ", "#####"],
+    ["#####", "   # ", "   # ", "#  # ", " ##  "],
+    ["#   #", "#  # ", "###  ", "#  # ", "#   #"],
+    ["#    ", "#    ", "#    ", "#    ", "#####"],
+    ["#   #", "## ##", "# # #", "#   #", "#   #"],
+    ["#   #", "##  #", "# # #", "#  ##", "#   #"],
+    [" ### ", "#   #", "#   #", "#   #", " ### "],
+    ["#### ", "#   #", "#### ", "#    ", "#    "],
+    [" ### ", "#   #", "# # #", "#  # ", " ## #"],
+    ["#### ", "#   #", "#### ", "#  # ", "#   #"],
+    [" ####", "#    ", " ### ", "    #", "#### "],
+    ["#####", "  #  ", "  #  ", "  #  ", "  #  "],
+    ["#   #", "#   #", "#   #", "#   #", " ### "],
+    ["#   #", "#   #", "#   #", " # # ", "  #  "],
+    ["#   #", "#   #", "# # #", "## ##", "#   #"],
+    ["#   #", " # # ", "  #  ", " # # ", "#   #"],
+    ["#   #", " # # ", "  #  ", "  #  ", "  #  "],
+    ["#####", "   # ", "  #  ", " #   ", "#####"],
+];
+
+/// Render a scored guess in large block letters, colored by correctness, for players who find
+/// normal text hard to read. Returns `None` when the letters would not fit in `columns` columns,
+/// so that the guess can be printed normally instead.
+///
+/// When color is disabled, letters are drawn with `#` when correct, `+` when in the word, and `.`
+/// when not in the word.
+pub fn big_guess(guess: &Guess, columns: usize, style: Style) -> Option<String> {
+    // Each letter is followed by a column of space
+    if guess.word.len() * 6 > columns {
+        return None;
+    }
+
+    let glyphs = guess
+        .word
+        .chars()
+        .map(|letter| {
+            let index = ALPHABET
+                .iter()
+                .position(|l| *l == letter.to_ascii_lowercase())?;
+            Some(BIG_FONT[index])
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut rendered = String::new();
+
+    for row in 0..5 {
+        for (glyph, correctness) in glyphs.iter().zip(&guess.correctness) {
+            let pixel = match (style.color, correctness) {
+                (true, correctness) => paint("█", *correctness, style),
+                (false, Correctness::Correct) => String::from("#"),
+                (false, Correctness::CorrectLetter) => String::from("+"),
+                (false, Correctness::Incorrect) => String::from("."),
+            };
+
+            for dot in glyph[row].chars() {
+                match dot {
+                    '#' => rendered += &pixel,
+                    _ => rendered.push(' '),
+                }
+            }
+            rendered.push(' ');
+        }
+        rendered.push('\n');
+    }
+
+    Some(rendered)
+}
+
+/// Render the letters known to be in their correct position as a template such as
+/// `[_ r a _ _]`, with underscores for positions not found yet.
+pub fn template(greens: &[Option<char>]) -> String {
+    let letters: Vec<String> = greens
+        .iter()
+        .map(|g| g.map_or(String::from("_"), String::from))
+        .collect();
+    format!("[{}]", letters.join(" "))
+}
+
+/// Render positional letter counts as CSV, with a row per letter from A to Z and a column per
+/// position, under a `letter,1,2,...` header.
+pub fn matrix_csv(frequencies: &[[usize; 26]]) -> String {
+    let positions: Vec<String> = (1..=frequencies.len()).map(|p| p.to_string()).collect();
+    let mut csv = format!("letter,{}\n", positions.join(","));
+
+    for (i, letter) in ALPHABET.iter().enumerate() {
+        let counts: Vec<String> = frequencies.iter().map(|f| f[i].to_string()).collect();
+        csv += &format!("{},{}\n", letter, counts.join(","));
+    }
+    csv
+}
+
+/// Letters of the keyboard, from A to Z.
+pub const ALPHABET: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+/// Prompt for the next guess, such as `(2/6) [12 left]> `. The template and count of possible
+/// answers are left out in blind mode, as they would give away the board.
+pub fn prompt(config: &Configuration, words: &WordLists, game: &Game) -> String {
+    let mut prompt = format!("({}/{})", game.guesses_made() + 1, game.tries());
+    if config.show_template && !config.blind {
+        prompt += &format!(" {}", template(&game.known_greens()));
+    }
+    if config.count_in_prompt && !config.blind {
+        let left = analysis::candidates(&words.pool, game.history()).len();
+        prompt += &format!(" [{} left]", left);
+    }
+    prompt + "> "
+}
+
+/// Print a guess with only the number of correct and present letters, not which ones they are.
+pub fn print_guess_counts(guess: &Guess) {
+    let (correct, present) = guess.counts();
+    println!("{}: {} correct, {} present", guess.word, correct, present);
+}
+
+/// Render difficulties from 0 to 1 as a bar of shaded blocks, one per letter, from light (easy)
+/// to dark and red (hard).
+pub fn heatmap_bar(difficulties: &[f64]) -> String {
+    difficulties
+        .iter()
+        .map(|d| {
+            let block = match d {
+                d if *d < 0.25 => "░".green(),
+                d if *d < 0.5 => "▒".yellow(),
+                d if *d < 0.75 => "▓".yellow(),
+                _ => "█".red(),
+            };
+            block.to_string()
+        })
+        .collect()
+}
+
+/// Print list of tried letters, in the order of the given keyboard, colored by the state known
+/// for each letter
+pub fn print_tried_letters(
+    state: impl Fn(char) -> Option<Correctness>,
+    keyboard: &[char],
+    style: Style,
+) {
+    print!("{}", tried_letters(state, keyboard, style));
+}
+
+/// Render the keyboard of [`print_tried_letters`]. Letters found in the word are rendered like
+/// the letters of guesses. Letters ruled out are red, or in parentheses without color, and
+/// untried letters are left as they are.
+pub fn tried_letters(
+    state: impl Fn(char) -> Option<Correctness>,
+    keyboard: &[char],
+    style: Style,
+) -> String {
+    keyboard
+        .iter()
+        .map(|&letter| match state(letter) {
+            Some(Correctness::Incorrect) if style.color => String::from(letter).red().to_string(),
+            Some(Correctness::Incorrect) => format!("({})", letter),
+            Some(correctness) => render_letter(letter, correctness, style),
+            None => String::from(letter),
+        })
+        .collect()
+}
+
+/// Render the letters whose state differs between two keyboard snapshots, such as
+/// `+e(correct) +r(present) -t(absent)`. Letters found in the word are marked with a `+`, and
+/// letters ruled out with a `-`.
+pub fn keyboard_diff(
+    before: &[Option<Correctness>; 26],
+    after: &[Option<Correctness>; 26],
+) -> String {
+    ALPHABET
+        .iter()
+        .zip(before.iter().zip(after))
+        .filter(|(_, (b, a))| b != a)
+        .filter_map(|(letter, (_, a))| match a {
+            Some(Correctness::Correct) => Some(format!("+{}(correct)", letter)),
+            Some(Correctness::CorrectLetter) => Some(format!("+{}(present)", letter)),
+            Some(Correctness::Incorrect) => Some(format!("-{}(absent)", letter)),
+            None => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Print a line of the letters from A to Z that have not been tried yet.
+pub fn print_letters_left(tried_letters: &HashMap<char, u8>) {
+    let untried: String = ALPHABET
+        .iter()
+        .filter(|letter| !tried_letters.contains_key(letter))
+        .collect();
+    println!("untried: {}", untried);
+}
+
+/// Print the stats of the games played so far.
+pub fn print_session(session: &Session) {
+    println!("Played: {}, Won: {}", session.played, session.won);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyboard_without_color_encodes_state() {
+        let style = Style::default();
+        let state = |letter| match letter {
+            'a' => Some(Correctness::Correct),
+            'b' => Some(Correctness::CorrectLetter),
+            'c' => Some(Correctness::Incorrect),
+            _ => None,
+        };
+        assert_eq!(
+            tried_letters(state, &['a', 'b', 'c', 'd'], style),
+            "A[b](c)d"
+        );
+
+        // Uppercase letters of case-sensitive games can't be told apart by case
+        let style = Style {
+            case_sensitive: true,
+            ..style
+        };
+        let state = |letter| match letter {
+            'A' => Some(Correctness::Correct),
+            'C' => Some(Correctness::Incorrect),
+            _ => None,
+        };
+        assert_eq!(tried_letters(state, &['A', 'C', 'D'], style), "{A}(C)D");
+        assert_eq!(render_letter('C', Correctness::Incorrect, style), "C");
+    }
+
+    #[test]
+    fn tile_colors_match() {
+        for correctness in [
+            Correctness::Correct,
+            Correctness::CorrectLetter,
+            Correctness::Incorrect,
+        ] {
+            let (r, g, b) = tile_rgb(correctness);
+            assert_eq!(
+                tile_color(correctness),
+                format!("#{:02x}{:02x}{:02x}", r, g, b)
+            );
+        }
+    }
+
+    #[test]
+    fn share_stats_count_greens_by_guess() {
+        assert_eq!(share_stats(&[]), None);
+
+        let history = crate::game::parse_board("crane:xxgxg,slate:xxgyg,shake:gggxg", 5).unwrap();
+        assert_eq!(share_stats(&history).unwrap(), "Greens by guess: 2, 2, 4");
+        assert_eq!(share_stats(&history[..1]).unwrap(), "Greens by guess: 2");
+    }
+
+    #[test]
+    fn template_shows_known_greens() {
+        let mut game = Game::new(String::from("slate"), &Configuration::default());
+        assert_eq!(template(&game.known_greens()), "[_ _ _ _ _]");
+
+        game.guess("crane");
+        assert_eq!(template(&game.known_greens()), "[_ _ a _ e]");
+
+        game.guess("spite");
+        assert_eq!(template(&game.known_greens()), "[s _ a t e]");
+    }
+
+    #[test]
+    fn prompt_counts_possible_answers() {
+        let config = Configuration {
+            count_in_prompt: true,
+            ..Configuration::default()
+        };
+        let source = "header\n\nblimp\ncrane\nplate\nslant\nslate\n";
+        let words = WordLists::from_source(source, &config).unwrap();
+        let mut game = Game::new(String::from("slate"), &config);
+        assert_eq!(prompt(&config, &words, &game), "(1/6) [5 left]> ");
+
+        game.guess("crane");
+        assert_eq!(prompt(&config, &words, &game), "(2/6) [2 left]> ");
+
+        let blind = Configuration {
+            blind: true,
+            ..config
+        };
+        assert_eq!(prompt(&blind, &words, &game), "(2/6)> ");
+    }
+}
@@ -0,0 +1,272 @@
+use crate::game::{Game, HintState};
+use crate::sha256::sha256_hex;
+use crate::words::WORD_PUNCTUATION;
+
+/// Version of the resume code format. Version 1 codes had no checksum, version 2 codes did not
+/// identify their target word, and version 3 codes did not keep hints. None are accepted any more.
+const VERSION: &str = "4";
+
+/// Number of hex digits of the checksum kept in a code.
+const CHECKSUM_LENGTH: usize = 8;
+
+/// Characters of the URL-safe base64 alphabet.
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Minimal state of an unfinished game, to be resumed from a short code instead of a save file.
+pub struct ResumeCode {
+    /// Seed the target word was picked with.
+    pub seed: u64,
+
+    /// Number of guess tries before the game is over.
+    pub tries: u32,
+
+    /// Guesses made so far, in order.
+    pub guesses: Vec<String>,
+
+    /// Hints left and revealed, so that resuming doesn't refill the hints spent.
+    pub hints: HintState,
+
+    /// Hash of the target word, from [`ResumeCode::target_hash`]. The seed alone does not pick
+    /// the word back when the game was started with other options, such as `--target`,
+    /// `--hardest`, or pool filters, so the word is checked with this when resuming.
+    pub target_hash: String,
+}
+
+impl ResumeCode {
+    /// Code to resume a game in progress, whose target word was picked with `seed`.
+    pub fn for_game(game: &Game, seed: u64) -> ResumeCode {
+        ResumeCode {
+            seed,
+            tries: game.tries(),
+            guesses: game.history().iter().map(|g| g.word.clone()).collect(),
+            hints: game.hint_state(),
+            target_hash: ResumeCode::target_hash(game.target()),
+        }
+    }
+
+    /// Hash identifying a target word in a code, without spelling it out.
+    pub fn target_hash(target: &str) -> String {
+        checksum(target)
+    }
+
+    /// Whether the code was made for a game with the given target word.
+    pub fn matches(&self, target: &str) -> bool {
+        self.target_hash == ResumeCode::target_hash(target)
+    }
+
+    /// Encode the state as a base64 code, with a checksum of the state.
+    ///
+    /// The checksum is a truncated SHA-256 hash of the state, so a code that was edited by hand
+    /// (for example to remove guesses) is refused. It only deters casual tampering: anyone who
+    /// reads this code can compute a valid checksum.
+    pub fn encode(&self) -> String {
+        let revealed: Vec<String> = self.hints.revealed.iter().map(usize::to_string).collect();
+        let state = format!(
+            "{}:{}:{}:{}:{}:{}:{}:{}",
+            VERSION,
+            self.seed,
+            self.tries,
+            self.guesses.join(","),
+            self.hints.hints,
+            revealed.join(","),
+            self.hints.revealed_absent.iter().collect::<String>(),
+            self.target_hash
+        );
+        let code = format!("{}:{}", state, checksum(&state));
+        encode_base64(code.as_bytes())
+    }
+
+    /// Decode a code made by [`ResumeCode::encode`].
+    pub fn decode(code: &str) -> Result<ResumeCode, String> {
+        let malformed = || format!("malformed resume code '{}'", code);
+
+        let state = decode_base64(code.trim()).ok_or_else(malformed)?;
+        let state = String::from_utf8(state).map_err(|_| malformed())?;
+
+        let fields: Vec<&str> = state.split(':').collect();
+        let version = fields.first().copied().unwrap_or("");
+        if version != VERSION {
+            return Err(format!("unsupported resume code version '{}'", version));
+        }
+
+        let [_, seed, tries, guesses, hints, revealed, revealed_absent, target_hash, sum] =
+            fields[..]
+        else {
+            return Err(malformed());
+        };
+
+        let (signed, _) = state.rsplit_once(':').ok_or_else(malformed)?;
+        if sum != checksum(signed) {
+            return Err(format!(
+                "resume code '{}' does not match its checksum",
+                code
+            ));
+        }
+
+        let guesses: Vec<String> = guesses
+            .split(',')
+            .filter(|g| !g.is_empty())
+            .map(String::from)
+            .collect();
+        if guesses.iter().any(|g| {
+            !g.chars()
+                .all(|c| c.is_ascii_alphabetic() || WORD_PUNCTUATION.contains(&c))
+        }) {
+            return Err(malformed());
+        }
+
+        if !revealed_absent.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(malformed());
+        }
+        let hints = HintState {
+            hints: hints.parse().map_err(|_| malformed())?,
+            revealed: revealed
+                .split(',')
+                .filter(|p| !p.is_empty())
+                .map(|p| p.parse().map_err(|_| malformed()))
+                .collect::<Result<_, _>>()?,
+            revealed_absent: revealed_absent.chars().collect(),
+        };
+
+        Ok(ResumeCode {
+            seed: seed.parse().map_err(|_| malformed())?,
+            tries: tries.parse().map_err(|_| malformed())?,
+            guesses,
+            hints,
+            target_hash: target_hash.to_string(),
+        })
+    }
+}
+
+/// Checksum of the state encoded in a code.
+fn checksum(state: &str) -> String {
+    sha256_hex(state.as_bytes())[..CHECKSUM_LENGTH].to_string()
+}
+
+/// Encode bytes as unpadded URL-safe base64.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            encoded.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+/// Decode unpadded URL-safe base64, or `None` if it is not valid.
+fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let sextets: Vec<u32> = encoded
+        .bytes()
+        .map(|c| BASE64.iter().position(|b| *b == c).map(|p| p as u32))
+        .collect::<Option<_>>()?;
+
+    let mut bytes = Vec::new();
+    for chunk in sextets.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, s)| n | s << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn punctuated_guesses_round_trip() {
+        let code = ResumeCode {
+            seed: 7,
+            tries: 6,
+            guesses: vec![String::from("don't"), String::from("re-do")],
+            hints: HintState::default(),
+            target_hash: ResumeCode::target_hash("crane"),
+        };
+        let decoded = ResumeCode::decode(&code.encode()).unwrap();
+        assert_eq!(decoded.guesses, code.guesses);
+    }
+
+    /// Code of a game on "slate" with two guesses made.
+    fn code() -> ResumeCode {
+        ResumeCode {
+            seed: 12345,
+            tries: 6,
+            guesses: vec![String::from("crane"), String::from("blimp")],
+            hints: HintState {
+                hints: 1,
+                revealed: vec![0, 3],
+                revealed_absent: vec!['z'],
+            },
+            target_hash: ResumeCode::target_hash("slate"),
+        }
+    }
+
+    /// Edit the state of a code without updating its checksum.
+    fn tamper(code: &str, edit: impl Fn(&str) -> String) -> String {
+        let state = String::from_utf8(decode_base64(code).unwrap()).unwrap();
+        encode_base64(edit(&state).as_bytes())
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        let decoded = ResumeCode::decode(&code().encode()).unwrap();
+        assert_eq!(decoded.seed, 12345);
+        assert_eq!(decoded.tries, 6);
+        assert_eq!(decoded.guesses, ["crane", "blimp"]);
+        assert_eq!(decoded.hints, code().hints);
+        assert!(decoded.matches("slate"));
+        assert!(!decoded.matches("crane"));
+    }
+
+    #[test]
+    fn tampered_codes_fail_the_checksum() {
+        let encoded = code().encode();
+        let edits: [fn(&str) -> String; 4] = [
+            |state| state.replace("crane,blimp", "crane"),
+            |state| state.replace("blimp", "slate"),
+            |state| state.replace(":6:", ":9:"),
+            |state| state.replace(":1:0,3:z:", ":3:::"),
+        ];
+        for edit in edits {
+            let tampered = tamper(&encoded, edit);
+            assert_ne!(tampered, encoded);
+            let error = ResumeCode::decode(&tampered).err().unwrap();
+            assert!(error.ends_with("does not match its checksum"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn resumed_games_keep_their_hints() {
+        let config = crate::cli::Configuration {
+            hints: 2,
+            ..crate::cli::Configuration::default()
+        };
+        let mut game = Game::new(String::from("slate"), &config);
+        game.guess("crane");
+        game.hint().unwrap();
+        game.absent_hint().unwrap();
+
+        let code = ResumeCode::decode(&ResumeCode::for_game(&game, 7).encode()).unwrap();
+
+        let mut resumed = Game::new(String::from("slate"), &config);
+        for guess in &code.guesses {
+            resumed.guess(guess);
+        }
+        resumed.restore_hints(code.hints);
+        assert_eq!(resumed.hint_state(), game.hint_state());
+        assert_eq!(resumed.hints(), 0);
+        assert!(resumed.hint().is_none());
+    }
+}
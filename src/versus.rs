@@ -0,0 +1,274 @@
+//! Racing other players on the same word over TCP.
+//!
+//! The host shares a seed (not the word) with each player that joins, and every player picks the
+//! target word from their own word lists with that seed. Players therefore get the same word as
+//! long as they run with the same word lists and filters. Once their game is over, players send
+//! back their result, and the host sends everyone the final ranking.
+//!
+//! To show that the word was not changed along the way, the host commits to it up front: it
+//! shares the SHA-256 hash of the word followed by a random salt, and reveals both the word and
+//! the salt at the end. Anyone can verify the commitment by hashing them again, for example
+//! with `printf '%s' "<word><salt>" | sha256sum`. Joined players verify it automatically.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::sha256::sha256_hex;
+
+/// How long the host waits for joined players to finish once its own game is over. Players who
+/// haven't sent their result by then are ranked as having quit or disconnected.
+const RESULT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Commitment to a target word: the SHA-256 hash of the word followed by the salt, in hex.
+pub fn commitment(target: &str, salt: &str) -> String {
+    sha256_hex(format!("{}{}", target, salt).as_bytes())
+}
+
+/// Word and salt revealed by the host at the end of a race, with the ranking.
+pub struct Reveal {
+    /// The target word.
+    pub target: String,
+
+    /// The salt hashed with the target word.
+    pub salt: String,
+
+    /// The final ranking.
+    pub ranking: String,
+}
+
+/// How a player's game ended.
+#[derive(Clone, Copy)]
+pub struct Finish {
+    /// Whether the player guessed the word.
+    pub won: bool,
+
+    /// Number of guesses made.
+    pub guesses: u32,
+}
+
+/// A player's place in the ranking.
+struct Standing {
+    /// Name of the player: "host", or the address a joined player connected from.
+    name: String,
+
+    /// How the player's game ended, or `None` if they quit or disconnected.
+    finish: Option<Finish>,
+}
+
+/// A hosted race, accepting players until the host's own game is over.
+pub struct Host {
+    /// Connections of the players that joined.
+    players: Arc<Mutex<Vec<TcpStream>>>,
+
+    /// Whether players can still join.
+    open: Arc<AtomicBool>,
+}
+
+impl Host {
+    /// Listen for players on the given address, sharing `seed` and the `commitment` to the
+    /// target word with each one that joins.
+    pub fn start(address: &str, seed: u64, commitment: String) -> Result<Host, String> {
+        let listener = TcpListener::bind(address)
+            .map_err(|e| format!("could not listen on '{}': {}", address, e))?;
+
+        let players = Arc::new(Mutex::new(Vec::new()));
+        let open = Arc::new(AtomicBool::new(true));
+
+        let (accepted, accepting) = (players.clone(), open.clone());
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                if !accepting.load(Ordering::SeqCst) {
+                    let _ = writeln!(stream, "CLOSED");
+                    continue;
+                }
+                if write!(stream, "SEED {}\nCOMMIT {}\n", seed, commitment).is_ok() {
+                    accepted.lock().unwrap().push(stream);
+                }
+            }
+        });
+
+        Ok(Host { players, open })
+    }
+
+    /// Stop accepting players, wait for every joined player to finish (for up to
+    /// [`RESULT_TIMEOUT`]), and send each one the revealed target word and salt, and the ranking.
+    /// Returns the ranking.
+    pub fn finish(self, host: Option<Finish>, target: &str, salt: &str) -> String {
+        self.open.store(false, Ordering::SeqCst);
+        let players = std::mem::take(&mut *self.players.lock().unwrap());
+        let deadline = Instant::now() + RESULT_TIMEOUT;
+
+        let mut standings = vec![Standing {
+            name: String::from("host"),
+            finish: host,
+        }];
+
+        for stream in &players {
+            let name = stream
+                .peer_addr()
+                .map_or_else(|_| String::from("unknown"), |a| a.to_string());
+            standings.push(Standing {
+                name,
+                finish: read_result(stream, deadline),
+            });
+        }
+
+        let ranking = ranking(standings);
+        for mut stream in &players {
+            // Players that disconnected just miss out on the ranking
+            let _ = write!(stream, "REVEAL {} {}\nRANKING\n{}", target, salt, ranking);
+        }
+        ranking
+    }
+}
+
+/// A joined race.
+pub struct Join {
+    /// Connection to the host.
+    stream: BufReader<TcpStream>,
+}
+
+impl Join {
+    /// Join the race hosted at the given address, returning the shared seed and the host's
+    /// commitment to the target word.
+    pub fn connect(address: &str) -> Result<(Join, u64, String), String> {
+        let stream = TcpStream::connect(address)
+            .map_err(|e| format!("could not connect to '{}': {}", address, e))?;
+        let mut stream = BufReader::new(stream);
+
+        let mut read_line = || {
+            let mut line = String::new();
+            stream
+                .read_line(&mut line)
+                .map(|_| line.trim().to_string())
+                .map_err(|e| format!("lost connection to the host: {}", e))
+        };
+
+        let seed = read_line()?;
+        if seed == "CLOSED" {
+            return Err(String::from("the race has already finished"));
+        }
+        let commitment = read_line()?;
+
+        let unexpected = || String::from("unexpected response from the host");
+        let seed = seed.strip_prefix("SEED ").ok_or_else(unexpected)?;
+        let seed = seed.parse().map_err(|_| unexpected())?;
+        let commitment = commitment.strip_prefix("COMMIT ").ok_or_else(unexpected)?;
+
+        Ok((Join { stream }, seed, commitment.to_string()))
+    }
+
+    /// Send the result of the game to the host, and wait for the revealed target word and the
+    /// ranking.
+    pub fn finish(mut self, finish: Option<Finish>) -> Result<Reveal, String> {
+        let lost = |e: std::io::Error| format!("lost connection to the host: {}", e);
+
+        let result = match finish {
+            Some(finish) => format!("RESULT {} {}", finish.won, finish.guesses),
+            None => String::from("QUIT"),
+        };
+        writeln!(self.stream.get_mut(), "{}", result).map_err(lost)?;
+
+        let mut response = String::new();
+        std::io::Read::read_to_string(&mut self.stream, &mut response).map_err(lost)?;
+
+        let disconnected = || String::from("the host disconnected before sending the ranking");
+        let (reveal, ranking) = response
+            .split_once("\nRANKING\n")
+            .ok_or_else(disconnected)?;
+        let reveal = reveal.strip_prefix("REVEAL ").ok_or_else(disconnected)?;
+        let (target, salt) = reveal.split_once(' ').ok_or_else(disconnected)?;
+
+        Ok(Reveal {
+            target: target.to_string(),
+            salt: salt.to_string(),
+            ranking: ranking.to_string(),
+        })
+    }
+}
+
+/// Wait for a player's result until the deadline, or `None` if they quit, disconnected, or
+/// didn't finish in time.
+fn read_result(stream: &TcpStream, deadline: Instant) -> Option<Finish> {
+    // A zero timeout would block forever, so players past the deadline get a moment to answer
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    stream
+        .set_read_timeout(Some(timeout.max(Duration::from_millis(1))))
+        .ok()?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).ok()?;
+
+    let mut fields = line.split_whitespace();
+    if fields.next()? != "RESULT" {
+        return None;
+    }
+    Some(Finish {
+        won: fields.next()?.parse().ok()?,
+        guesses: fields.next()?.parse().ok()?,
+    })
+}
+
+/// Rank the players: winners by fewest guesses, then players who lost, then players who quit or
+/// disconnected.
+fn ranking(mut standings: Vec<Standing>) -> String {
+    standings.sort_by_key(|s| match s.finish {
+        Some(Finish { won: true, guesses }) => (0, guesses),
+        Some(Finish { won: false, .. }) => (1, 0),
+        None => (2, 0),
+    });
+
+    let mut ranking = String::new();
+    for (place, standing) in standings.iter().enumerate() {
+        let outcome = match standing.finish {
+            Some(Finish { won: true, guesses }) => format!("solved in {}", guesses),
+            Some(Finish { won: false, .. }) => String::from("did not solve"),
+            None => String::from("quit or disconnected"),
+        };
+        ranking += &format!("{}. {} ({})\n", place + 1, standing.name, outcome);
+    }
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commitment_round_trips() {
+        let salt = "0123456789abcdef";
+        let commitment = commitment("crane", salt);
+        assert_eq!(commitment, sha256_hex(b"crane0123456789abcdef"));
+        assert_eq!(commitment, super::commitment("crane", salt));
+        assert_ne!(commitment, super::commitment("slate", salt));
+        assert_ne!(commitment, super::commitment("crane", "fedcba9876543210"));
+    }
+
+    #[test]
+    fn silent_players_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let player = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        let start = Instant::now();
+        let deadline = start + Duration::from_millis(50);
+        assert!(read_result(&stream, deadline).is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(player);
+    }
+
+    #[test]
+    fn results_are_read() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut player = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        writeln!(player, "RESULT true 4").unwrap();
+        let finish = read_result(&stream, Instant::now() + Duration::from_secs(5)).unwrap();
+        assert!(finish.won);
+        assert_eq!(finish.guesses, 4);
+    }
+}
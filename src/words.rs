@@ -0,0 +1,505 @@
+use std::collections::HashSet;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::analysis;
+use crate::cli::{Configuration, Selection};
+
+/// Embedded list of possible answers, one word per line.
+pub const ANSWERS: &str = include_str!("wordle-nyt-answers-alphabetical.txt");
+
+/// Common words to play with if the embedded list has no usable words, so that the game stays
+/// playable if the list is broken.
+const FALLBACK_ANSWERS: &str = "about\nbrave\ncrane\ndream\nearth\nflame\ngrape\nhouse\nlight\nmoney\nnight\nocean\nplant\nquiet\nriver\nsmile\ntrain\nunder\nvoice\nwater\n";
+
+/// Which word list guesses are validated against.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Validity {
+    /// Only possible answers are accepted as guesses. This is the stricter, classic rule.
+    Answers,
+
+    /// Any allowed word is accepted as a guess, including words that cannot be the answer. This
+    /// is more lenient, and allows probing with words that can be ruled out as the answer.
+    Full,
+}
+
+impl std::str::FromStr for Validity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "answers" => Ok(Validity::Answers),
+            "full" => Ok(Validity::Full),
+            _ => Err(format!(
+                "unknown validity '{}' (expected answers or full)",
+                s
+            )),
+        }
+    }
+}
+
+/// Words that can be the answer, and words that are accepted as guesses.
+pub struct WordLists {
+    /// Possible answers.
+    pub answers: Vec<String>,
+
+    /// Words the target word is picked from: the answers, narrowed by any configured filters.
+    pub pool: Vec<String>,
+
+    /// Words accepted as guesses. Always includes every answer.
+    pub allowed: Vec<String>,
+
+    /// Set of the possible answers, to check guesses against quickly.
+    answer_set: HashSet<String>,
+
+    /// Set of the words accepted as guesses, to check guesses against quickly.
+    allowed_set: HashSet<String>,
+}
+
+impl WordLists {
+    /// Load the embedded answers, and the additional allowed guesses if configured.
+    pub fn load(config: &Configuration) -> Result<WordLists, String> {
+        WordLists::from_source(ANSWERS, config)
+    }
+
+    /// Load answers from the text of an answer list in the embedded list's format, and the
+    /// additional allowed guesses if configured. The fallback list is only used if the source
+    /// has no words at all, not when it just has none of the configured length.
+    pub fn from_source(source: &str, config: &Configuration) -> Result<WordLists, String> {
+        // The first two lines of the embedded list are skipped
+        let source = source.splitn(3, '\n').nth(2).unwrap_or("");
+        let mut answers = words_list(source, config);
+
+        let has_words = source
+            .split('\n')
+            .any(|line| !sanitize_word(line, config.case_sensitive, true).is_empty());
+        if !has_words {
+            eprintln!("warning: the embedded word list has no usable words, using a fallback list");
+            answers = words_list(FALLBACK_ANSWERS, config);
+        }
+
+        if answers.is_empty() {
+            return Err(format!(
+                "no possible answers are {} letters in length",
+                config.guess_letters
+            ));
+        }
+
+        let mut allowed = answers.clone();
+        if let Some(path) = &config.allowed_file {
+            allowed.extend(read_words(path, config)?);
+            allowed.sort();
+            allowed.dedup();
+        }
+
+        let answer_set = answers.iter().cloned().collect();
+        let allowed_set = allowed.iter().cloned().collect();
+
+        let mut pool = answers.clone();
+
+        // Narrow the pool to words containing every required letter
+        if let Some(letters) = &config.require_letters {
+            pool.retain(|w| letters.chars().all(|c| w.contains(c)));
+            if pool.is_empty() {
+                return Err(format!("no possible answers contain all of '{}'", letters));
+            }
+        }
+
+        // Narrow the pool to words made only of letters from the bank
+        if let Some(bank) = &config.letter_bank {
+            pool.retain(|w| w.chars().all(|c| bank.contains(c)));
+            if pool.is_empty() {
+                return Err(format!(
+                    "no possible answers are made only of the letters '{}'",
+                    bank
+                ));
+            }
+        }
+
+        // Leave out words with repeated letters
+        if config.no_duplicates {
+            pool.retain(|w| !has_repeated_letters(w));
+            if pool.is_empty() {
+                return Err(String::from(
+                    "no possible answers are left without repeated letters",
+                ));
+            }
+        }
+
+        Ok(WordLists {
+            answers,
+            pool,
+            allowed,
+            answer_set,
+            allowed_set,
+        })
+    }
+
+    /// Words accepted as guesses under the given validity rule.
+    pub fn valid_guesses(&self, validity: Validity) -> &[String] {
+        match validity {
+            Validity::Answers => &self.answers,
+            Validity::Full => &self.allowed,
+        }
+    }
+
+    /// Set of the words accepted as guesses under the given validity rule, to check a guess
+    /// against without scanning [`WordLists::valid_guesses`].
+    pub fn guess_set(&self, validity: Validity) -> &HashSet<String> {
+        match validity {
+            Validity::Answers => &self.answer_set,
+            Validity::Full => &self.allowed_set,
+        }
+    }
+
+    /// Allowed words made of exactly the same letters as `word`, excluding `word` itself.
+    pub fn anagrams(&self, word: &str) -> Vec<&str> {
+        let signature = letter_signature(word);
+        self.allowed
+            .iter()
+            .filter(|w| *w != word && letter_signature(w) == signature)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether a word can be the answer.
+    pub fn is_answer(&self, word: &str) -> bool {
+        self.answer_set.contains(word)
+    }
+
+    /// Pick the target word of a game: the configured target if there is one, or a word from the
+    /// pool by the configured selection. The same seeded `rng` always picks the same word.
+    ///
+    /// With `--ladder K`, the word is picked among those sharing at least K letters with the
+    /// `previous` target word. When no word qualifies, a warning is printed and the word is picked
+    /// from the whole pool instead.
+    pub fn pick_target(
+        &self,
+        config: &Configuration,
+        rng: &mut impl Rng,
+        previous: Option<&str>,
+    ) -> String {
+        if let Some(target) = &config.target {
+            return target.clone();
+        }
+
+        let mut pool = self.pool.clone();
+
+        if let (Some(shared), Some(previous)) = (config.ladder, previous) {
+            let rungs: Vec<String> = pool
+                .iter()
+                .filter(|w| *w != previous && shared_letters(w, previous) >= shared)
+                .cloned()
+                .collect();
+
+            if rungs.is_empty() {
+                eprintln!(
+                    "warning: no word shares {} letters with '{}', picking from the whole pool",
+                    shared, previous
+                );
+            } else {
+                pool = rungs;
+            }
+        }
+
+        match config.selection {
+            Selection::Random if config.prefer_unique => {
+                // Words with repeated letters are a quarter as likely to be picked
+                pool.choose_weighted(rng, |w| if has_repeated_letters(w) { 1 } else { 4 })
+                    .unwrap()
+                    .to_string()
+            }
+            Selection::Random => pool.choose(rng).unwrap().to_string(),
+            selection => {
+                // Difficulty is estimated against all answers, then picked from the (possibly
+                // narrowed) pool
+                let length = config.guess_letters as usize;
+                let frequencies = analysis::positional_frequencies(&self.answers, length);
+                let hardest = selection == Selection::Hardest;
+                analysis::by_difficulty(&pool, &frequencies, hardest)
+                    .unwrap()
+                    .to_string()
+            }
+        }
+    }
+}
+
+/// Letters of a word in sorted order. Words with the same signature are anagrams of each other.
+pub fn letter_signature(word: &str) -> Vec<u8> {
+    let mut letters = word.as_bytes().to_vec();
+    letters.sort_unstable();
+    letters
+}
+
+/// Whether a word contains any letter more than once.
+pub fn has_repeated_letters(word: &str) -> bool {
+    letter_signature(word).windows(2).any(|w| w[0] == w[1])
+}
+
+/// Number of letters two words have in common, counting repeated letters as many times as both
+/// words contain them.
+pub fn shared_letters(a: &str, b: &str) -> usize {
+    let (a, b) = (letter_signature(a), letter_signature(b));
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+
+    // Walk both sorted signatures at once
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    shared
+}
+
+/// Punctuation that can be part of a word, as in "don't" or "mother-in-law".
+pub const WORD_PUNCTUATION: [char; 2] = ['\'', '-'];
+
+/// Whether a word contains punctuation that can be part of a word.
+pub fn is_punctuated(word: &str) -> bool {
+    word.contains(WORD_PUNCTUATION)
+}
+
+/// Trim and lowercase a word, removing any non-alphabetic characters. The case is kept as it is
+/// when `case_sensitive` is set, and [`WORD_PUNCTUATION`] is kept when `keep_punctuation` is set.
+pub fn sanitize_word(word: &str, case_sensitive: bool, keep_punctuation: bool) -> String {
+    let word = word.trim();
+    let word = if case_sensitive {
+        word.to_string()
+    } else {
+        word.to_lowercase()
+    };
+
+    word.chars()
+        .filter(|c| c.is_ascii_alphabetic() || (keep_punctuation && WORD_PUNCTUATION.contains(c)))
+        .collect()
+}
+
+/// Parse a newline-separated list of words, keeping only words of the configured length.
+pub fn words_list(all_words: &str, config: &Configuration) -> Vec<String> {
+    words_iter(all_words.split('\n'), config).collect()
+}
+
+/// Sanitize lines into words, keeping only words of the configured length, one at a time as
+/// they are needed. Operations that don't need random access to the words (like validation or
+/// analysis) can use this instead of [`words_list`] to avoid holding a whole dictionary in memory.
+///
+/// Words with [`WORD_PUNCTUATION`], such as "don't", are dropped with a warning rather than
+/// becoming a different word without it, unless punctuation is allowed, in which case it is
+/// kept as part of the word.
+pub fn words_iter<'a, S: AsRef<str>>(
+    lines: impl IntoIterator<Item = S> + 'a,
+    config: &'a Configuration,
+) -> impl Iterator<Item = String> + 'a {
+    let length = config.guess_letters as usize;
+
+    lines
+        .into_iter()
+        .filter_map(move |line| {
+            let line = line.as_ref();
+            if config.allow_punctuation || !is_punctuated(line) {
+                return Some(sanitize_word(
+                    line,
+                    config.case_sensitive,
+                    config.allow_punctuation,
+                ));
+            }
+
+            // Only mention words that would have been playable, with or without punctuation
+            let kept = sanitize_word(line, config.case_sensitive, true);
+            let stripped = sanitize_word(line, config.case_sensitive, false);
+            if kept.len() == length || stripped.len() == length {
+                eprintln!(
+                    "warning: dropping '{}' from the word list, as it contains punctuation (see --allow-punctuation)",
+                    line.trim()
+                );
+            }
+            None
+        })
+        .filter(move |word| word.len() == length)
+}
+
+/// Stream the words of a dictionary file, without reading the whole file up front. Reading stops
+/// at the first line that cannot be read.
+pub fn read_words<'a>(
+    path: &str,
+    config: &'a Configuration,
+) -> Result<impl Iterator<Item = String> + 'a, String> {
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("could not read '{}': {}", path, e))?;
+    let lines = std::io::BufRead::lines(std::io::BufReader::new(file)).map_while(Result::ok);
+    Ok(words_iter(lines, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    const PUNCTUATED: [&str; 4] = ["don't", "mother-in-law", "re-do", "crane"];
+
+    #[test]
+    fn punctuated_words_are_dropped_by_default() {
+        let config = Configuration::default();
+        let words: Vec<String> = words_iter(PUNCTUATED, &config).collect();
+        assert_eq!(words, ["crane"]);
+    }
+
+    #[test]
+    fn punctuated_words_are_kept_when_allowed() {
+        let config = Configuration {
+            allow_punctuation: true,
+            ..Configuration::default()
+        };
+        let words: Vec<String> = words_iter(PUNCTUATED, &config).collect();
+        assert_eq!(words, ["don't", "re-do", "crane"]);
+    }
+
+    #[test]
+    fn case_is_kept_when_case_sensitive() {
+        assert_eq!(sanitize_word(" Crane ", true, false), "Crane");
+        assert_ne!(
+            sanitize_word("Crane", true, false),
+            sanitize_word("crane", true, false)
+        );
+        assert_eq!(sanitize_word(" Crane ", false, false), "crane");
+    }
+
+    #[test]
+    fn empty_source_falls_back() {
+        let config = Configuration::default();
+        for source in ["", "header\n\n", "header\n\n\n123\n!!!\n"] {
+            let words = WordLists::from_source(source, &config).unwrap();
+            assert_eq!(words.answers, words_list(FALLBACK_ANSWERS, &config));
+        }
+    }
+
+    #[test]
+    fn no_words_of_length_does_not_fall_back() {
+        let config = Configuration {
+            guess_letters: 6,
+            ..Configuration::default()
+        };
+        let source = "header\n\ncrane\nslate\n";
+        let error = WordLists::from_source(source, &config).err().unwrap();
+        assert_eq!(error, "no possible answers are 6 letters in length");
+    }
+
+    #[test]
+    fn validity_decides_whether_allowed_words_are_guesses() {
+        let path = std::env::temp_dir().join(format!("rust_wordle_allowed_{}", std::process::id()));
+        std::fs::write(&path, "fjord\n").unwrap();
+        let config = Configuration {
+            allowed_file: Some(path.to_string_lossy().into_owned()),
+            ..Configuration::default()
+        };
+        let words = WordLists::from_source("header\n\ncrane\nslate\n", &config).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(!words.is_answer("fjord"));
+        assert!(!words.guess_set(Validity::Answers).contains("fjord"));
+        assert!(words.guess_set(Validity::Answers).contains("crane"));
+        assert!(words.guess_set(Validity::Full).contains("fjord"));
+        assert!(words.guess_set(Validity::Full).contains("crane"));
+    }
+
+    #[test]
+    fn guess_sets_match_guess_lists() {
+        let words = WordLists::load(&Configuration::default()).unwrap();
+        let probes = [
+            "crane", "slate", "xxxxx", "fjord", "aback", "zonal", "Crane", "",
+        ];
+
+        for validity in [Validity::Answers, Validity::Full] {
+            let list = words.valid_guesses(validity);
+            let set = words.guess_set(validity);
+            assert_eq!(set.len(), list.len());
+            for word in list.iter().map(String::as_str).chain(probes) {
+                assert_eq!(
+                    set.contains(word),
+                    list.iter().any(|w| w == word),
+                    "{}",
+                    word
+                );
+            }
+        }
+    }
+
+    /// Word lists of the given answers.
+    fn lists(answers: &str, config: &Configuration) -> WordLists {
+        WordLists::from_source(&format!("header\n\n{}", answers), config).unwrap()
+    }
+
+    /// Targets picked with 200 different seeds.
+    fn picks(words: &WordLists, config: &Configuration, previous: Option<&str>) -> Vec<String> {
+        (0..200)
+            .map(|seed| words.pick_target(config, &mut StdRng::seed_from_u64(seed), previous))
+            .collect()
+    }
+
+    #[test]
+    fn configured_target_is_picked() {
+        let config = Configuration {
+            target: Some(String::from("qajaq")),
+            ladder: Some(5),
+            ..Configuration::default()
+        };
+        let words = lists("crane\nslate\n", &config);
+        assert_eq!(picks(&words, &config, Some("crane")), vec!["qajaq"; 200]);
+    }
+
+    #[test]
+    fn ladder_rungs_share_letters() {
+        let config = Configuration {
+            ladder: Some(3),
+            ..Configuration::default()
+        };
+        let words = lists("crane\nslate\nstale\nblimp\nfjord\n", &config);
+        for pick in picks(&words, &config, Some("least")) {
+            assert!(pick == "slate" || pick == "stale", "{}", pick);
+        }
+
+        // With no word sharing enough letters, any word of the pool can be picked
+        let unmatched: HashSet<String> =
+            picks(&words, &config, Some("gypsy")).into_iter().collect();
+        assert_eq!(unmatched.len(), words.pool.len());
+    }
+
+    #[test]
+    fn unique_letters_are_preferred() {
+        let config = Configuration {
+            prefer_unique: true,
+            ..Configuration::default()
+        };
+        let words = lists("crane\nllama\n", &config);
+        let picks = picks(&words, &config, None);
+        let unique = picks.iter().filter(|w| *w == "crane").count();
+        assert!(
+            unique > 3 * (picks.len() - unique) / 2,
+            "{} of {}",
+            unique,
+            picks.len()
+        );
+    }
+
+    #[test]
+    fn difficulty_selection_is_deterministic() {
+        for selection in [Selection::Hardest, Selection::Easiest] {
+            let config = Configuration {
+                selection,
+                ..Configuration::default()
+            };
+            let words = lists("crane\nslate\nfuzzy\njazzy\nsaint\n", &config);
+            let picks: HashSet<String> = picks(&words, &config, None).into_iter().collect();
+            assert_eq!(picks.len(), 1);
+        }
+    }
+}
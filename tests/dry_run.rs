@@ -0,0 +1,33 @@
+//! `--dry-run` checks the configuration and word lists, and exits without playing.
+
+use std::process::{Command, Output};
+
+/// Run the game with the given arguments and no input.
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rust_wordle"))
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn valid_configuration_succeeds() {
+    let output = run(&["--dry-run", "--no-color"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Dry run:"));
+}
+
+#[test]
+fn missing_dictionary_fails() {
+    let output = run(&["--dry-run", "--allowed", "/nonexistent/words.txt"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("could not read"));
+}
+
+#[test]
+fn empty_pool_fails() {
+    let output = run(&["--dry-run", "--require-letters", "qxz"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no possible answers"));
+}
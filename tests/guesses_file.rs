@@ -0,0 +1,28 @@
+//! `--guesses-file` plays the guesses of a file instead of reading them from standard input.
+
+use std::process::{Command, Stdio};
+
+#[test]
+fn guesses_from_a_file_win() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rust_wordle"))
+        .args([
+            "--no-color",
+            "--target",
+            "slate",
+            "--report",
+            "--guesses-file",
+        ])
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/win.txt"
+        ))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("crAnE"));
+    assert!(stdout.contains("SLATE"));
+    assert!(stdout.contains(r#""guesses":["crane","slate"],"tries":6,"result":"success""#));
+}